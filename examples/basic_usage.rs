@@ -14,8 +14,8 @@ use payrex::{Client, Error, ErrorKind};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let api_key =
-        std::env::var("PAYREX_API_KEY").unwrap_or_else(|_| "your_secret_api_key".to_string());
+    let api_key = std::env::var("PAYREX_API_KEY")
+        .unwrap_or_else(|_| "sk_test_your_secret_api_key".to_string());
 
     let client = Client::new(api_key);
 
@@ -74,6 +74,8 @@ fn handle_error(error: Error) {
             message,
             status_code,
             request_id,
+            error_code,
+            errors,
         } => {
             println!("  Error Type: {:?}", kind);
             println!("  Message: {}", message);
@@ -83,6 +85,16 @@ fn handle_error(error: Error) {
             if let Some(id) = request_id {
                 println!("  Request ID: {}", id);
             }
+            if let Some(code) = error_code {
+                println!("  Error Code: {}", code);
+            }
+            for field_error in errors {
+                println!(
+                    "  Field {}: {}",
+                    field_error.param.as_deref().unwrap_or("-"),
+                    field_error.message
+                );
+            }
 
             match kind {
                 ErrorKind::Authentication => {
@@ -5,14 +5,17 @@
 
 use crate::{
     Result,
-    config::Config,
-    http::HttpClient,
+    config::{BodyEncoding, Config, ConfigBuilder},
+    http::{self, HttpClient, RequestInspection},
     resources::{
         BillingStatementLineItems, BillingStatements, CheckoutSessions, Customers, PaymentIntents,
         Payments, Payouts, Refunds, Webhooks,
     },
+    retry::RetryPolicy,
+    types::{FeeSchedule, Metadata},
 };
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Main client for the PayRex API.
 ///
@@ -26,7 +29,7 @@ use std::sync::Arc;
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), payrex::Error> {
-///     let client = Client::new("your_secret_key");
+///     let client = Client::new("sk_test_your_secret_key");
 ///
 ///     // Access API resources
 ///     // let payment = client.payment_intents().create(...).await?;
@@ -60,6 +63,49 @@ impl Client {
         })
     }
 
+    /// Returns a [`ClientBuilder`] for configuring and creating a client in one chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use payrex::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::builder()
+    ///     .api_key("sk_test_123")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// assert_eq!(client.config().timeout(), Duration::from_secs(10));
+    /// # Ok::<(), payrex::Error>(())
+    /// ```
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Runs `op` with an idempotency key derived from `key` on every `POST` request it sends.
+    ///
+    /// Use this to give a composed operation (e.g. create then capture) one key, so retrying the
+    /// whole operation with the same key does not repeat requests PayRex already processed. Each
+    /// `POST` gets its own key numbered by call order, `{key}-1`, `{key}-2` and so on, so `op`
+    /// must send its requests in the same order every time it runs. Methods that take an
+    /// explicit key, such as [`PaymentIntents::create_with_idempotency_key`], keep their own key.
+    ///
+    /// The key is task-local: requests sent from tasks spawned inside `op` do not inherit it.
+    pub async fn with_idempotency<F, Fut, T>(&self, key: &str, op: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        http::scope_idempotency_key(key.to_string(), op()).await
+    }
+
+    /// Returns the configuration the client was created with.
+    #[must_use]
+    pub fn config(&self) -> &Config {
+        self.http.config()
+    }
+
     #[must_use]
     pub fn payment_intents(&self) -> PaymentIntents {
         PaymentIntents::new(Arc::clone(&self.http))
@@ -106,6 +152,148 @@ impl Client {
     }
 }
 
+/// Builder for [`Client`].
+///
+/// Accepts the same settings as [`ConfigBuilder`] and builds the client directly. Use
+/// [`Client::with_config`] to share a prebuilt [`Config`].
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    config: ConfigBuilder,
+}
+
+impl ClientBuilder {
+    /// See [`ConfigBuilder::api_key`].
+    #[must_use]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config = self.config.api_key(api_key);
+        self
+    }
+
+    /// See [`ConfigBuilder::api_base_url`].
+    #[must_use]
+    pub fn api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.config = self.config.api_base_url(url);
+        self
+    }
+
+    /// See [`ConfigBuilder::timeout`].
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.timeout(timeout);
+        self
+    }
+
+    /// See [`ConfigBuilder::connect_timeout`].
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.connect_timeout(timeout);
+        self
+    }
+
+    /// See [`ConfigBuilder::max_retries`].
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.config = self.config.max_retries(max_retries);
+        self
+    }
+
+    /// See [`ConfigBuilder::retry_delay`].
+    #[must_use]
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.config = self.config.retry_delay(delay);
+        self
+    }
+
+    /// See [`ConfigBuilder::retry_policy`].
+    #[must_use]
+    pub fn retry_policy(mut self, policy: Box<dyn RetryPolicy>) -> Self {
+        self.config = self.config.retry_policy(policy);
+        self
+    }
+
+    /// See [`ConfigBuilder::user_agent`].
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config = self.config.user_agent(user_agent);
+        self
+    }
+
+    /// See [`ConfigBuilder::body_encoding`].
+    #[must_use]
+    pub fn body_encoding(mut self, encoding: BodyEncoding) -> Self {
+        self.config = self.config.body_encoding(encoding);
+        self
+    }
+
+    /// See [`ConfigBuilder::header`].
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.config = self.config.header(name, value);
+        self
+    }
+
+    /// See [`ConfigBuilder::default_metadata`].
+    #[must_use]
+    pub fn default_metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.config = self.config.default_metadata(metadata);
+        self
+    }
+
+    /// See [`ConfigBuilder::warn_on_livemode_mismatch`].
+    #[must_use]
+    pub fn warn_on_livemode_mismatch(mut self, enabled: bool) -> Self {
+        self.config = self.config.warn_on_livemode_mismatch(enabled);
+        self
+    }
+
+    /// See [`ConfigBuilder::fee_schedule`].
+    #[must_use]
+    pub fn fee_schedule(mut self, schedule: FeeSchedule) -> Self {
+        self.config = self.config.fee_schedule(schedule);
+        self
+    }
+
+    /// See [`ConfigBuilder::inspect`].
+    #[must_use]
+    pub fn inspect(mut self, hook: Arc<dyn Fn(&RequestInspection<'_>) + Send + Sync>) -> Self {
+        self.config = self.config.inspect(hook);
+        self
+    }
+
+    /// See [`ConfigBuilder::max_concurrent_requests`].
+    #[must_use]
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.config = self.config.max_concurrent_requests(max);
+        self
+    }
+
+    /// See [`ConfigBuilder::error_body_snippet_len`].
+    #[must_use]
+    pub fn error_body_snippet_len(mut self, len: usize) -> Self {
+        self.config = self.config.error_body_snippet_len(len);
+        self
+    }
+
+    /// See [`ConfigBuilder::skip_key_validation`].
+    #[must_use]
+    pub fn skip_key_validation(mut self, skip: bool) -> Self {
+        self.config = self.config.skip_key_validation(skip);
+        self
+    }
+
+    /// See [`ConfigBuilder::test_mode`].
+    #[must_use]
+    pub fn test_mode(mut self, enabled: bool) -> Self {
+        self.config = self.config.test_mode(enabled);
+        self
+    }
+
+    /// Builds the configuration and creates the client.
+    pub fn build(self) -> Result<Client> {
+        Client::with_config(self.config.build()?)
+    }
+}
+
 impl std::fmt::Debug for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Client")
@@ -117,16 +305,17 @@ impl std::fmt::Debug for Client {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::mock_client;
 
     #[test]
     fn test_client_new() {
-        let client = Client::new("test_key");
+        let client = Client::new("sk_test_key");
         assert!(std::sync::Arc::strong_count(&client.http) == 1);
     }
 
     #[test]
     fn test_client_try_new() {
-        let result = Client::try_new("test_key");
+        let result = Client::try_new("sk_test_key");
         assert!(result.is_ok());
     }
 
@@ -138,14 +327,269 @@ mod tests {
 
     #[test]
     fn test_client_with_config() {
-        let config = Config::new("test_key").unwrap();
+        let config = Config::new("sk_test_key").unwrap();
         let result = Client::with_config(config);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_client_builder() {
+        let client = Client::builder()
+            .api_key("sk_test_123")
+            .api_base_url("http://localhost:8080")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .max_retries(1)
+            .user_agent("my-app/1.0")
+            .build()
+            .unwrap();
+
+        let config = client.config();
+        assert_eq!(config.api_key(), "sk_test_123");
+        assert_eq!(config.api_base_url(), "http://localhost:8080");
+        assert_eq!(config.timeout(), Duration::from_secs(5));
+        assert_eq!(config.connect_timeout(), Some(Duration::from_secs(1)));
+        assert_eq!(config.max_retries(), 1);
+        assert_eq!(config.user_agent(), "my-app/1.0");
+        assert!(config.is_test_mode());
+    }
+
+    #[test]
+    fn test_client_builder_requires_api_key() {
+        assert!(Client::builder().build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_idempotency_derives_a_key_per_post() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+
+        client
+            .with_idempotency("order-42", || async {
+                let _: serde_json::Value = client.http.post("/payment_intents", &()).await?;
+                let _: serde_json::Value = client.http.get("/payment_intents/pi_123").await?;
+                let _: serde_json::Value = client
+                    .http
+                    .post("/payment_intents/pi_123/capture", &())
+                    .await?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let _: serde_json::Value = client.http.post("/refunds", &()).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let keys: Vec<_> = requests
+            .iter()
+            .map(|r| {
+                (
+                    r.method.as_str().to_string(),
+                    r.headers
+                        .get(http::IDEMPOTENCY_KEY_HEADER)
+                        .map(|v| v.to_str().unwrap().to_string()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("POST".to_string(), Some("order-42-1".to_string())),
+                ("GET".to_string(), None),
+                ("POST".to_string(), Some("order-42-2".to_string())),
+                ("POST".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_idempotency_keys_are_stable_across_retries() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/payment_intents"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/payment_intents/pi_123/capture"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/payment_intents/pi_123/capture"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("sk_test_123")
+            .api_base_url(server.uri())
+            .max_retries(1)
+            .retry_delay(Duration::from_millis(1))
+            .build()
+            .unwrap();
+
+        let op = || async {
+            let _: serde_json::Value = client.http.post("/payment_intents", &()).await?;
+            let _: serde_json::Value = client
+                .http
+                .post("/payment_intents/pi_123/capture", &())
+                .await?;
+            Ok(())
+        };
+        client.with_idempotency("order-42", op).await.unwrap();
+        client.with_idempotency("order-42", op).await.unwrap();
+
+        let keys: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| {
+                (
+                    r.url.path().to_string(),
+                    r.headers
+                        .get(http::IDEMPOTENCY_KEY_HEADER)
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_string(),
+                )
+            })
+            .collect();
+        let create = ("/payment_intents".to_string(), "order-42-1".to_string());
+        let capture = (
+            "/payment_intents/pi_123/capture".to_string(),
+            "order-42-2".to_string(),
+        );
+        assert_eq!(
+            keys,
+            [
+                create.clone(),
+                capture.clone(),
+                capture.clone(),
+                create,
+                capture
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_methods_accept_none() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": [], "has_more": false })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let payout_id = crate::types::PayoutId::new("po_123");
+
+        assert!(
+            client
+                .billing_statements()
+                .list(None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(client.customers().list(None).await.unwrap().is_empty());
+        assert!(client.payments().list(None).await.unwrap().is_empty());
+        assert!(client.payouts().list(None).await.unwrap().is_empty());
+        assert!(
+            client
+                .payouts()
+                .list_transactions(&payout_id, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(client.webhooks().list(None).await.unwrap().is_empty());
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(requests.iter().all(|r| r.body.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_default_metadata_is_merged_into_creates() {
+        use crate::resources::customers::CreateCustomer;
+        use crate::types::Currency;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "cus_123",
+                "livemode": false,
+                "created_at": 1_620_000_000,
+                "updated_at": 1_620_000_000,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = Client::builder()
+            .api_key("sk_test_123")
+            .api_base_url(server.uri())
+            .max_retries(0)
+            .body_encoding(BodyEncoding::Json)
+            .default_metadata([("tenant_id", "acme"), ("source", "sdk")])
+            .build()
+            .unwrap();
+
+        let params = CreateCustomer::new(
+            Currency::PHP,
+            "juan@example.com".to_string(),
+            "Juan".to_string(),
+        );
+        client.customers().create(params.clone()).await.unwrap();
+        client
+            .customers()
+            .create(params.metadata([("tenant_id", "globex"), ("order", "42")]))
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let metadata: Vec<serde_json::Value> = requests
+            .iter()
+            .map(|r| {
+                serde_json::from_slice::<serde_json::Value>(&r.body).unwrap()["metadata"].clone()
+            })
+            .collect();
+        assert_eq!(
+            metadata,
+            [
+                serde_json::json!({ "tenant_id": "acme", "source": "sdk" }),
+                serde_json::json!({ "tenant_id": "globex", "source": "sdk", "order": "42" }),
+            ]
+        );
+    }
+
     #[test]
     fn test_client_clone() {
-        let client = Client::new("test_key");
+        let client = Client::new("sk_test_key");
         let cloned = client.clone();
 
         assert!(std::sync::Arc::ptr_eq(&client.http, &cloned.http));
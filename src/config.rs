@@ -3,40 +3,82 @@
 //! This module provides configuration options for customizing the behavior
 //! of the PayRex client, including timeouts, retries, and API endpoints.
 
-use crate::{API_BASE_URL, Error, Result};
+use crate::{
+    API_BASE_URL, Error, Result,
+    http::{Inspector, RequestInspection},
+    retry::{ExponentialBackoff, RetryPolicy},
+    types::{FeeSchedule, Metadata, Secret},
+};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+use std::sync::Arc;
 use std::time::Duration;
 
+/// The default for [`ConfigBuilder::error_body_snippet_len`].
+const DEFAULT_ERROR_BODY_SNIPPET_LEN: usize = 512;
+
+/// How request bodies are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyEncoding {
+    /// `application/x-www-form-urlencoded`, the encoding PayRex documents for all endpoints.
+    #[default]
+    Form,
+
+    /// `application/json`. Avoids form-encoding limitations for deeply nested payloads.
+    Json,
+}
+
 /// Configuration for the PayRex client.
 ///
 /// Use [`ConfigBuilder`] to construct a configuration with custom settings.
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub(crate) api_key: String,
+    pub(crate) api_key: Secret<String>,
     pub(crate) api_base_url: String,
     pub(crate) timeout: Duration,
+    pub(crate) connect_timeout: Option<Duration>,
     pub(crate) max_retries: u32,
     pub(crate) retry_delay: Duration,
+    pub(crate) retry_policy: Option<Arc<dyn RetryPolicy>>,
     pub(crate) user_agent: String,
+    pub(crate) body_encoding: BodyEncoding,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) default_metadata: Metadata,
+    pub(crate) warn_on_livemode_mismatch: bool,
+    pub(crate) fee_schedule: FeeSchedule,
+    pub(crate) inspector: Option<Inspector>,
+    pub(crate) max_concurrent_requests: Option<usize>,
+    pub(crate) error_body_snippet_len: usize,
     pub(crate) test_mode: bool,
 }
 
 impl Config {
+    /// Creates a configuration with default settings.
+    ///
+    /// Returns [`Error::InvalidApiKey`] if the key is not a well-formed secret key, see
+    /// [`ConfigBuilder::skip_key_validation`].
     pub fn new(api_key: impl Into<String>) -> Result<Self> {
         let api_key = api_key.into();
-
-        if api_key.is_empty() {
-            return Err(Error::InvalidApiKey("API key cannot be empty".to_string()));
-        }
+        validate_api_key(&api_key)?;
 
         let test_mode = api_key.starts_with("sk_test_");
 
         Ok(Self {
-            api_key,
+            api_key: Secret::new(api_key),
             api_base_url: API_BASE_URL.to_string(),
             timeout: Duration::from_secs(30),
+            connect_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_millis(500),
+            retry_policy: None,
             user_agent: format!("payrex-rust/{}", crate::VERSION),
+            body_encoding: BodyEncoding::default(),
+            default_headers: HeaderMap::new(),
+            default_metadata: Metadata::new(),
+            warn_on_livemode_mismatch: false,
+            fee_schedule: FeeSchedule::default(),
+            inspector: None,
+            max_concurrent_requests: None,
+            error_body_snippet_len: DEFAULT_ERROR_BODY_SNIPPET_LEN,
             test_mode,
         })
     }
@@ -48,7 +90,7 @@ impl Config {
 
     #[must_use]
     pub fn api_key(&self) -> &str {
-        &self.api_key
+        self.api_key.expose()
     }
 
     #[must_use]
@@ -61,6 +103,12 @@ impl Config {
         self.timeout
     }
 
+    /// Returns the timeout applied only to establishing a connection, if one was configured.
+    #[must_use]
+    pub const fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
     #[must_use]
     pub const fn max_retries(&self) -> u32 {
         self.max_retries
@@ -71,28 +119,127 @@ impl Config {
         self.retry_delay
     }
 
+    /// Returns the retry policy, falling back to [`ExponentialBackoff`] built from
+    /// [`Config::retry_delay`] and [`Config::max_retries`] when none was configured.
+    #[must_use]
+    pub fn retry_policy(&self) -> Arc<dyn RetryPolicy> {
+        self.retry_policy.clone().unwrap_or_else(|| {
+            Arc::new(ExponentialBackoff::new(self.retry_delay, self.max_retries))
+        })
+    }
+
     #[must_use]
     pub fn user_agent(&self) -> &str {
         &self.user_agent
     }
 
+    /// Returns how request bodies are encoded.
+    #[must_use]
+    pub const fn body_encoding(&self) -> BodyEncoding {
+        self.body_encoding
+    }
+
+    /// Returns the extra headers sent with every request.
+    #[must_use]
+    pub const fn default_headers(&self) -> &HeaderMap {
+        &self.default_headers
+    }
+
+    /// Returns the metadata merged into every created resource.
+    #[must_use]
+    pub const fn default_metadata(&self) -> &Metadata {
+        &self.default_metadata
+    }
+
+    /// Returns `true` if responses are checked against [`Config::is_test_mode`].
+    #[must_use]
+    pub const fn warn_on_livemode_mismatch(&self) -> bool {
+        self.warn_on_livemode_mismatch
+    }
+
+    /// Returns the fee rates used by [`Payments::estimate_fee`](crate::resources::Payments::estimate_fee).
+    #[must_use]
+    pub const fn fee_schedule(&self) -> &FeeSchedule {
+        &self.fee_schedule
+    }
+
+    /// Returns the maximum number of requests the client sends at once, if limited.
+    #[must_use]
+    pub const fn max_concurrent_requests(&self) -> Option<usize> {
+        self.max_concurrent_requests
+    }
+
+    /// Returns how many bytes of a response body are kept in [`Error::Deserialization`].
+    #[must_use]
+    pub const fn error_body_snippet_len(&self) -> usize {
+        self.error_body_snippet_len
+    }
+
     #[must_use]
     pub const fn is_test_mode(&self) -> bool {
         self.test_mode
     }
 }
 
+/// Checks that `api_key` looks like a PayRex secret key: `sk_test_` or `sk_live_` followed by
+/// letters, digits, and underscores.
+fn validate_api_key(api_key: &str) -> Result<()> {
+    if api_key.is_empty() {
+        return Err(Error::invalid_api_key("API key cannot be empty"));
+    }
+    if api_key.chars().any(char::is_whitespace) {
+        return Err(Error::invalid_api_key(
+            "API key contains whitespace, check for spaces or newlines copied along with it",
+        ));
+    }
+    if !api_key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(Error::invalid_api_key(
+            "API key may only contain letters, digits, and underscores",
+        ));
+    }
+    if api_key.starts_with("pk_") {
+        return Err(Error::invalid_api_key(
+            "API key is a public key, use the secret key (sk_test_... or sk_live_...) instead",
+        ));
+    }
+    match api_key
+        .strip_prefix("sk_test_")
+        .or_else(|| api_key.strip_prefix("sk_live_"))
+    {
+        Some("") => Err(Error::invalid_api_key("API key is incomplete")),
+        Some(_) => Ok(()),
+        None => Err(Error::invalid_api_key(
+            "API key must start with sk_test_ or sk_live_",
+        )),
+    }
+}
+
 /// Builder for [`Config`].
 ///
 /// Provides a fluent interface for constructing a configuration with custom settings.
 #[derive(Debug, Default)]
 pub struct ConfigBuilder {
-    api_key: Option<String>,
+    api_key: Option<Secret<String>>,
     api_base_url: Option<String>,
     timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
     max_retries: Option<u32>,
     retry_delay: Option<Duration>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
     user_agent: Option<String>,
+    body_encoding: BodyEncoding,
+    default_headers: HeaderMap,
+    default_metadata: Metadata,
+    warn_on_livemode_mismatch: bool,
+    fee_schedule: Option<FeeSchedule>,
+    inspector: Option<Inspector>,
+    max_concurrent_requests: Option<usize>,
+    error_body_snippet_len: Option<usize>,
+    invalid_header: Option<String>,
+    skip_key_validation: bool,
     test_mode: bool,
 }
 
@@ -104,7 +251,7 @@ impl ConfigBuilder {
 
     #[must_use]
     pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
-        self.api_key = Some(api_key.into());
+        self.api_key = Some(Secret::new(api_key.into()));
         self
     }
 
@@ -120,6 +267,14 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the timeout for establishing a connection, separate from the overall request
+    /// [`timeout`](Self::timeout).
+    #[must_use]
+    pub const fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
     #[must_use]
     pub const fn max_retries(mut self, max_retries: u32) -> Self {
         self.max_retries = Some(max_retries);
@@ -132,12 +287,127 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets a custom retry policy, replacing the default exponential backoff. When set,
+    /// [`max_retries`](Self::max_retries) and [`retry_delay`](Self::retry_delay) are ignored.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: Box<dyn RetryPolicy>) -> Self {
+        self.retry_policy = Some(Arc::from(policy));
+        self
+    }
+
     #[must_use]
     pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
         self.user_agent = Some(user_agent.into());
         self
     }
 
+    /// Sets how request bodies are encoded. Defaults to [`BodyEncoding::Form`].
+    #[must_use]
+    pub const fn body_encoding(mut self, encoding: BodyEncoding) -> Self {
+        self.body_encoding = encoding;
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. a tenant or correlation ID.
+    ///
+    /// Invalid header names or values, and attempts to set `Authorization`, are reported as an
+    /// [`Error::Config`] by [`build`](Self::build). Headers managed by the SDK, such as
+    /// `User-Agent` and `Content-Type`, take precedence over headers set here.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.default_headers.insert(name, value);
+            }
+            _ => {
+                self.invalid_header
+                    .get_or_insert_with(|| format!("Invalid header: {name}"));
+            }
+        }
+        self
+    }
+
+    /// Merges `headers` into the headers sent with every request. See [`header`](Self::header).
+    #[must_use]
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        for (name, value) in &headers {
+            self.default_headers.insert(name, value.clone());
+        }
+        self
+    }
+
+    /// Sets metadata stamped onto every payment intent, customer, checkout session, refund and
+    /// billing statement created through the client, e.g. a `tenant_id`.
+    ///
+    /// Keys supplied on the create request take precedence over these defaults.
+    #[must_use]
+    pub fn default_metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.default_metadata = metadata.into();
+        self
+    }
+
+    /// Logs a `tracing` warning whenever a response's `livemode` disagrees with the client's
+    /// mode, e.g. a test-mode client receiving live data. Off by default.
+    ///
+    /// Enabling this parses every response body twice, so it is best suited to development and
+    /// staging environments.
+    #[must_use]
+    pub const fn warn_on_livemode_mismatch(mut self, enabled: bool) -> Self {
+        self.warn_on_livemode_mismatch = enabled;
+        self
+    }
+
+    /// Sets the fee rates used for fee estimates, for accounts with negotiated pricing.
+    /// Defaults to [`FeeSchedule::default`].
+    #[must_use]
+    pub fn fee_schedule(mut self, schedule: FeeSchedule) -> Self {
+        self.fee_schedule = Some(schedule);
+        self
+    }
+
+    /// Sets a hook called with the method, URL, request body, status and response body of every
+    /// request, including each retry attempt. Useful for debugging a single failing call without
+    /// enabling verbose logging.
+    ///
+    /// Secrets such as `client_secret` are redacted from the response body. Bodies are only
+    /// buffered for inspection when a hook is set.
+    #[must_use]
+    pub fn inspect(mut self, hook: Arc<dyn Fn(&RequestInspection<'_>) + Send + Sync>) -> Self {
+        self.inspector = Some(Inspector(hook));
+        self
+    }
+
+    /// Limits how many requests the client has in flight at once, across all clones of the
+    /// client. Further requests wait for a slot, which keeps bursts under PayRex's rate limit
+    /// instead of running into `429 Too Many Requests`. Unlimited by default.
+    ///
+    /// A slot is held while a request is sent and its response read, and released while waiting
+    /// to retry. [`build`](Self::build) rejects a limit of `0`.
+    #[must_use]
+    pub const fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max);
+        self
+    }
+
+    /// Sets how many bytes of a response body are kept in [`Error::Deserialization`] when the
+    /// body cannot be parsed. Defaults to 512; `0` leaves the body out entirely.
+    #[must_use]
+    pub const fn error_body_snippet_len(mut self, len: usize) -> Self {
+        self.error_body_snippet_len = Some(len);
+        self
+    }
+
+    /// Accepts any non-empty API key instead of requiring the `sk_test_` / `sk_live_` format,
+    /// e.g. for a proxy or mock server with its own keys. Off by default.
+    #[must_use]
+    pub const fn skip_key_validation(mut self, skip: bool) -> Self {
+        self.skip_key_validation = skip;
+        self
+    }
+
     #[must_use]
     pub const fn test_mode(mut self, enabled: bool) -> Self {
         self.test_mode = enabled;
@@ -147,25 +417,57 @@ impl ConfigBuilder {
     pub fn build(self) -> Result<Config> {
         let api_key = self
             .api_key
-            .ok_or_else(|| Error::Config("API key is required".to_string()))?;
+            .ok_or_else(|| Error::Config("API key is required".to_string()))?
+            .into_inner();
+
+        if self.skip_key_validation {
+            if api_key.is_empty() {
+                return Err(Error::invalid_api_key("API key cannot be empty"));
+            }
+        } else {
+            validate_api_key(&api_key)?;
+        }
 
-        if api_key.is_empty() {
-            return Err(Error::InvalidApiKey("API key cannot be empty".to_string()));
+        if self.max_concurrent_requests == Some(0) {
+            return Err(Error::config("max_concurrent_requests must be at least 1"));
+        }
+
+        if let Some(message) = self.invalid_header {
+            return Err(Error::Config(message));
+        }
+
+        if self.default_headers.contains_key(AUTHORIZATION) {
+            return Err(Error::Config(
+                "The Authorization header is set from the API key and cannot be overridden"
+                    .to_string(),
+            ));
         }
 
         let test_mode = self.test_mode || api_key.starts_with("sk_test_");
 
         Ok(Config {
-            api_key,
+            api_key: Secret::new(api_key),
             api_base_url: self
                 .api_base_url
                 .unwrap_or_else(|| API_BASE_URL.to_string()),
             timeout: self.timeout.unwrap_or(Duration::from_secs(30)),
+            connect_timeout: self.connect_timeout,
             max_retries: self.max_retries.unwrap_or(3),
             retry_delay: self.retry_delay.unwrap_or(Duration::from_millis(500)),
+            retry_policy: self.retry_policy,
             user_agent: self
                 .user_agent
                 .unwrap_or_else(|| format!("payrex-rust/{}", crate::VERSION)),
+            body_encoding: self.body_encoding,
+            default_headers: self.default_headers,
+            default_metadata: self.default_metadata,
+            warn_on_livemode_mismatch: self.warn_on_livemode_mismatch,
+            fee_schedule: self.fee_schedule.unwrap_or_default(),
+            inspector: self.inspector,
+            max_concurrent_requests: self.max_concurrent_requests,
+            error_body_snippet_len: self
+                .error_body_snippet_len
+                .unwrap_or(DEFAULT_ERROR_BODY_SNIPPET_LEN),
             test_mode,
         })
     }
@@ -177,8 +479,8 @@ mod tests {
 
     #[test]
     fn test_config_new() {
-        let config = Config::new("test_key").unwrap();
-        assert_eq!(config.api_key(), "test_key");
+        let config = Config::new("sk_test_key").unwrap();
+        assert_eq!(config.api_key(), "sk_test_key");
         assert_eq!(config.timeout(), Duration::from_secs(30));
         assert_eq!(config.max_retries(), 3);
     }
@@ -189,22 +491,160 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_api_key_validation() {
+        assert!(Config::new("sk_test_abc123").is_ok());
+        assert!(Config::new("sk_live_ABC_123").is_ok());
+
+        let result = Config::new("abc123");
+        assert!(matches!(result, Err(Error::InvalidApiKey(m)) if m.contains("sk_test_")));
+        let result = Config::new("sk_test_abc 123");
+        assert!(matches!(result, Err(Error::InvalidApiKey(m)) if m.contains("whitespace")));
+        let result = Config::new("sk_test_abc123\n");
+        assert!(matches!(result, Err(Error::InvalidApiKey(m)) if m.contains("whitespace")));
+        let result = Config::new("pk_test_abc123");
+        assert!(matches!(result, Err(Error::InvalidApiKey(m)) if m.contains("public key")));
+        assert!(Config::new("sk_test_").is_err());
+        assert!(Config::new("sk_test_abc\"").is_err());
+
+        let result = Config::builder().api_key("hello world").build();
+        assert!(matches!(result, Err(Error::InvalidApiKey(_))));
+    }
+
+    #[test]
+    fn test_config_builder_max_concurrent_requests() {
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .max_concurrent_requests(4)
+            .build()
+            .unwrap();
+        assert_eq!(config.max_concurrent_requests(), Some(4));
+        assert_eq!(
+            Config::new("sk_test_key")
+                .unwrap()
+                .max_concurrent_requests(),
+            None
+        );
+
+        let result = Config::builder()
+            .api_key("sk_test_key")
+            .max_concurrent_requests(0)
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_config_builder_error_body_snippet_len() {
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .error_body_snippet_len(64)
+            .build()
+            .unwrap();
+        assert_eq!(config.error_body_snippet_len(), 64);
+        assert_eq!(
+            Config::new("sk_test_key").unwrap().error_body_snippet_len(),
+            DEFAULT_ERROR_BODY_SNIPPET_LEN
+        );
+    }
+
+    #[test]
+    fn test_config_builder_skip_key_validation() {
+        let config = Config::builder()
+            .api_key("proxy_key")
+            .skip_key_validation(true)
+            .build()
+            .unwrap();
+        assert_eq!(config.api_key(), "proxy_key");
+
+        let result = Config::builder()
+            .api_key("")
+            .skip_key_validation(true)
+            .build();
+        assert!(matches!(result, Err(Error::InvalidApiKey(_))));
+    }
+
     #[test]
     fn test_config_builder() {
         let config = Config::builder()
-            .api_key("test_key")
+            .api_key("sk_live_key")
             .timeout(Duration::from_secs(60))
             .max_retries(5)
             .test_mode(true)
             .build()
             .unwrap();
 
-        assert_eq!(config.api_key(), "test_key");
+        assert_eq!(config.api_key(), "sk_live_key");
         assert_eq!(config.timeout(), Duration::from_secs(60));
         assert_eq!(config.max_retries(), 5);
         assert!(config.is_test_mode());
     }
 
+    #[test]
+    fn test_config_builder_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-correlation-id", HeaderValue::from_static("abc"));
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .header("X-Tenant-Id", "tenant_1")
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.default_headers()["x-tenant-id"], "tenant_1");
+        assert_eq!(config.default_headers()["x-correlation-id"], "abc");
+    }
+
+    #[test]
+    fn test_config_builder_rejects_authorization_header() {
+        let result = Config::builder()
+            .api_key("sk_test_key")
+            .header("Authorization", "Bearer other")
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer other"));
+        let result = Config::builder()
+            .api_key("sk_test_key")
+            .default_headers(headers)
+            .build();
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_config_builder_rejects_invalid_header() {
+        let result = Config::builder()
+            .api_key("sk_test_key")
+            .header("bad header", "value")
+            .build();
+        assert!(matches!(result, Err(Error::Config(m)) if m.contains("bad header")));
+    }
+
+    #[test]
+    fn test_config_builder_connect_timeout() {
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .timeout(Duration::from_secs(60))
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.timeout(), Duration::from_secs(60));
+        assert_eq!(config.connect_timeout(), Some(Duration::from_secs(5)));
+
+        let config = Config::new("sk_test_key").unwrap();
+        assert_eq!(config.connect_timeout(), None);
+    }
+
+    #[test]
+    fn test_config_debug_redacts_api_key() {
+        let config = Config::new("sk_live_supersecret").unwrap();
+        assert!(!format!("{config:?}").contains("supersecret"));
+
+        let builder = Config::builder().api_key("sk_live_supersecret");
+        assert!(!format!("{builder:?}").contains("supersecret"));
+    }
+
     #[test]
     fn test_config_builder_missing_api_key() {
         let result = Config::builder().timeout(Duration::from_secs(60)).build();
@@ -220,7 +660,11 @@ mod tests {
         let live_config = Config::new("sk_live_abc123").unwrap();
         assert!(!live_config.is_test_mode());
 
-        let other_config = Config::new("some_other_key").unwrap();
+        let other_config = Config::builder()
+            .api_key("some_other_key")
+            .skip_key_validation(true)
+            .build()
+            .unwrap();
         assert!(!other_config.is_test_mode());
     }
 
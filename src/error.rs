@@ -3,11 +3,35 @@
 //! This module provides comprehensive error handling using the `thiserror` crate.
 //! All errors implement `std::error::Error` and can be easily converted and propagated.
 
+use serde::Deserialize;
 use std::fmt;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Errors returned by the PayRex SDK.
+///
+/// This enum is `#[non_exhaustive]`: new variants may be added in minor releases, so matches
+/// outside this crate need a wildcard arm. Use the constructor helpers (e.g.
+/// [`Error::invalid_request`]) instead of variant literals when building errors.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::{Error, ErrorKind};
+///
+/// fn describe(error: &Error) -> &'static str {
+///     match error {
+///         Error::Api { kind: ErrorKind::NotFound, .. } | Error::NotFound(_) => "missing",
+///         Error::RateLimit { .. } => "slow down",
+///         _ => "something else",
+///     }
+/// }
+///
+/// assert_eq!(describe(&Error::not_found("pi_123")), "missing");
+/// assert_eq!(describe(&Error::timeout(std::time::Duration::from_secs(1))), "something else");
+/// ```
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     #[error("HTTP request failed: {0}")]
     Http(#[from] reqwest::Error),
@@ -18,11 +42,29 @@ pub enum Error {
         message: String,
         status_code: Option<u16>,
         request_id: Option<String>,
+        /// The machine-readable code of the first error in the response body, e.g.
+        /// `parameter_invalid_empty`.
+        error_code: Option<String>,
+        /// Field-level errors parsed from the response body. Empty when the body had none.
+        errors: Vec<FieldError>,
     },
 
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// A successful response body could not be parsed into the expected type.
+    #[error("Failed to deserialize response from {path}: {source}. Body: {body_snippet}")]
+    Deserialization {
+        /// The path of the endpoint that returned the body, e.g. `/payment_intents/pi_123`.
+        path: String,
+        /// The start of the body, with secrets redacted. Its length is set by
+        /// [`ConfigBuilder::error_body_snippet_len`](crate::ConfigBuilder::error_body_snippet_len).
+        body_snippet: String,
+        /// The underlying parse error.
+        #[source]
+        source: serde_json::Error,
+    },
+
     #[error("Configuration error: {0}")]
     Config(String),
 
@@ -49,14 +91,55 @@ pub enum Error {
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
-    #[error("Idempotency error: {0}")]
-    Idempotency(String),
+    /// An idempotency key was reused with different request parameters.
+    #[error("Idempotency error for key {key}: {message}")]
+    Idempotency {
+        /// The idempotency key sent with the conflicting request.
+        key: String,
+        /// The error body returned by PayRex.
+        message: String,
+    },
+
+    /// A webhook payload failed signature verification.
+    #[error("Webhook signature verification failed: {0}")]
+    SignatureVerification(String),
 
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+/// A single field-level error from a PayRex error response.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FieldError {
+    /// The request parameter the error is about, if any.
+    #[serde(default, alias = "parameter")]
+    pub param: Option<String>,
+    /// Machine-readable error code, e.g. `parameter_required`.
+    #[serde(default)]
+    pub code: String,
+    /// Human-readable description of the error.
+    #[serde(default, alias = "detail")]
+    pub message: String,
+}
+
+/// The `{"errors": [...]}` body PayRex returns for failed requests.
+#[derive(Deserialize)]
+struct ValidationErrors {
+    errors: Vec<FieldError>,
+}
+
+impl FieldError {
+    /// Parses the field errors out of an error response body, returning an empty list when the
+    /// body is not in the expected shape.
+    pub(crate) fn parse_all(body: &str) -> Vec<Self> {
+        serde_json::from_str::<ValidationErrors>(body)
+            .map(|body| body.errors)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ErrorKind {
     InvalidRequest,
     Authentication,
@@ -112,6 +195,8 @@ impl Error {
             message: message.into(),
             status_code: None,
             request_id: None,
+            error_code: None,
+            errors: Vec::new(),
         }
     }
 
@@ -122,9 +207,80 @@ impl Error {
             message: message.into(),
             status_code: Some(status_code),
             request_id: None,
+            error_code: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Creates an [`Error::Config`].
+    #[must_use]
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::Config(message.into())
+    }
+
+    /// Creates an [`Error::InvalidApiKey`].
+    #[must_use]
+    pub fn invalid_api_key(message: impl Into<String>) -> Self {
+        Self::InvalidApiKey(message.into())
+    }
+
+    /// Creates an [`Error::RateLimit`].
+    #[must_use]
+    pub const fn rate_limit(retry_after: Option<std::time::Duration>) -> Self {
+        Self::RateLimit { retry_after }
+    }
+
+    /// Creates an [`Error::Timeout`].
+    #[must_use]
+    pub const fn timeout(duration: std::time::Duration) -> Self {
+        Self::Timeout(duration)
+    }
+
+    /// Creates an [`Error::InvalidRequest`].
+    #[must_use]
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::InvalidRequest(message.into())
+    }
+
+    /// Creates an [`Error::NotFound`].
+    #[must_use]
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    /// Creates an [`Error::Authentication`].
+    #[must_use]
+    pub fn authentication(message: impl Into<String>) -> Self {
+        Self::Authentication(message.into())
+    }
+
+    /// Creates an [`Error::PermissionDenied`].
+    #[must_use]
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::PermissionDenied(message.into())
+    }
+
+    /// Creates an [`Error::Idempotency`] for the conflicting `key`.
+    #[must_use]
+    pub fn idempotency(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Idempotency {
+            key: key.into(),
+            message: message.into(),
         }
     }
 
+    /// Creates an [`Error::SignatureVerification`].
+    #[must_use]
+    pub fn signature_verification(message: impl Into<String>) -> Self {
+        Self::SignatureVerification(message.into())
+    }
+
+    /// Creates an [`Error::Internal`].
+    #[must_use]
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal(message.into())
+    }
+
     #[must_use]
     pub fn is_retryable(&self) -> bool {
         match self {
@@ -136,6 +292,17 @@ impl Error {
         }
     }
 
+    /// Returns how long the server asked us to wait before retrying.
+    ///
+    /// Only [`Error::RateLimit`] carries this; every other variant returns `None`.
+    #[must_use]
+    pub const fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::RateLimit { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub const fn status_code(&self) -> Option<u16> {
         match self {
@@ -144,6 +311,30 @@ impl Error {
         }
     }
 
+    /// Returns every field-level error reported by the API, so a form can flag all invalid
+    /// fields at once.
+    ///
+    /// Returns `None` for errors that did not come with field errors.
+    #[must_use]
+    pub fn validation_errors(&self) -> Option<&[FieldError]> {
+        match self {
+            Self::Api { errors, .. } if !errors.is_empty() => Some(errors),
+            _ => None,
+        }
+    }
+
+    /// Returns the original API error code, e.g. `parameter_invalid_empty`.
+    ///
+    /// Unlike [`ErrorKind`], which only reflects the HTTP status, this is the exact code PayRex
+    /// reported, so it is suitable for fine-grained handling and per-code metrics.
+    #[must_use]
+    pub fn error_code(&self) -> Option<&str> {
+        match self {
+            Self::Api { error_code, .. } => error_code.as_deref(),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn request_id(&self) -> Option<&str> {
         match self {
@@ -188,6 +379,102 @@ mod tests {
         assert!(!error.is_retryable());
     }
 
+    #[test]
+    fn test_field_errors_are_parsed() {
+        let body = r#"{"errors":[
+            {"code":"parameter_required","detail":"amount is required","parameter":"amount"},
+            {"code":"parameter_invalid","detail":"currency is invalid","parameter":"currency"},
+            {"code":"resource_not_found","detail":"customer not found"}
+        ]}"#;
+        let errors = FieldError::parse_all(body);
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0],
+            FieldError {
+                param: Some("amount".to_string()),
+                code: "parameter_required".to_string(),
+                message: "amount is required".to_string(),
+            }
+        );
+        assert_eq!(errors[1].param.as_deref(), Some("currency"));
+        assert_eq!(errors[2].param, None);
+
+        assert!(FieldError::parse_all("Bad Request").is_empty());
+        assert!(FieldError::parse_all(r#"{"error":"x"}"#).is_empty());
+    }
+
+    #[test]
+    fn test_error_validation_errors() {
+        assert_eq!(
+            Error::api(ErrorKind::InvalidRequest, "Bad request").validation_errors(),
+            None
+        );
+        assert_eq!(Error::invalid_request("x").validation_errors(), None);
+
+        let error = Error::Api {
+            kind: ErrorKind::InvalidRequest,
+            message: String::new(),
+            status_code: Some(400),
+            request_id: None,
+            error_code: None,
+            errors: FieldError::parse_all(
+                r#"{"errors":[{"code":"parameter_required","detail":"name is required","parameter":"name"}]}"#,
+            ),
+        };
+        let errors = error.validation_errors().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].param.as_deref(), Some("name"));
+    }
+
+    #[test]
+    fn test_error_retry_after() {
+        use std::time::Duration;
+
+        let error = Error::rate_limit(Some(Duration::from_secs(3)));
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(3)));
+
+        assert_eq!(Error::rate_limit(None).retry_after(), None);
+        assert_eq!(Error::timeout(Duration::from_secs(5)).retry_after(), None);
+        assert_eq!(
+            Error::api(ErrorKind::RateLimit, "Too many requests").retry_after(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_error_constructors() {
+        use std::time::Duration;
+
+        assert!(matches!(Error::config("c"), Error::Config(m) if m == "c"));
+        assert!(matches!(Error::invalid_api_key("k"), Error::InvalidApiKey(m) if m == "k"));
+        assert!(matches!(
+            Error::rate_limit(Some(Duration::from_secs(2))),
+            Error::RateLimit { retry_after: Some(d) } if d == Duration::from_secs(2)
+        ));
+        assert!(matches!(
+            Error::timeout(Duration::from_secs(5)),
+            Error::Timeout(d) if d == Duration::from_secs(5)
+        ));
+        assert!(matches!(Error::invalid_request("r"), Error::InvalidRequest(m) if m == "r"));
+        assert!(matches!(Error::not_found("n"), Error::NotFound(m) if m == "n"));
+        assert!(matches!(Error::authentication("a"), Error::Authentication(m) if m == "a"));
+        assert!(matches!(
+            Error::permission_denied("p"),
+            Error::PermissionDenied(m) if m == "p"
+        ));
+        assert!(matches!(
+            Error::idempotency("k", "i"),
+            Error::Idempotency { key, message } if key == "k" && message == "i"
+        ));
+        assert!(matches!(
+            Error::signature_verification("s"),
+            Error::SignatureVerification(m) if m == "s"
+        ));
+        assert!(matches!(Error::internal("x"), Error::Internal(m) if m == "x"));
+    }
+
     #[test]
     fn test_error_status_code() {
         let error = Error::api_with_status(ErrorKind::NotFound, "Not found", 404);
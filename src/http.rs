@@ -3,21 +3,237 @@
 //! This module provides a wrapper around `reqwest` with automatic retries,
 //! rate limiting, and proper error handling for the PayRex API.
 
-use crate::{Config, Error, ErrorKind, Result};
+use crate::{
+    BodyEncoding, Config, Error, ErrorKind, Result, error::FieldError, retry::RetryPolicy,
+    types::Metadata,
+};
 use base64::{Engine as _, engine::general_purpose};
-use reqwest::{Client as ReqwestClient, RequestBuilder, Response, StatusCode, header};
+use reqwest::{Client as ReqwestClient, Method, RequestBuilder, Response, StatusCode, header};
 use serde::{Serialize, de::DeserializeOwned};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Header carrying the idempotency key of a request.
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+tokio::task_local! {
+    /// Idempotency key from which the keys of `POST`s sent within [`scope_idempotency_key`] are
+    /// derived.
+    static SCOPED_IDEMPOTENCY_KEY: ScopedIdempotencyKey;
+}
+
+/// The key of an idempotency scope and the number of `POST`s sent in it so far.
+struct ScopedIdempotencyKey {
+    key: String,
+    sent: AtomicU32,
+}
+
+impl ScopedIdempotencyKey {
+    /// Returns the key for the next `POST` of the scope: `{key}-1`, `{key}-2`, and so on.
+    ///
+    /// Numbering by call order gives each request its own key, while re-running the same
+    /// operation with the same scope key reproduces the same keys.
+    fn next(&self) -> String {
+        let n = self.sent.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{}-{n}", self.key)
+    }
+}
+
+/// Runs `future` with idempotency keys derived from `key` on every `POST` it sends that does not
+/// carry an explicit key.
+pub(crate) async fn scope_idempotency_key<F: Future>(key: String, future: F) -> F::Output {
+    let scoped = ScopedIdempotencyKey {
+        key,
+        sent: AtomicU32::new(0),
+    };
+    SCOPED_IDEMPOTENCY_KEY.scope(scoped, future).await
+}
+
+/// Response fields whose values are replaced with `[REDACTED]` before being inspected.
+const REDACTED_FIELDS: &[&str] = &["client_secret", "secret_key"];
+
+/// One request and its response, passed to the hook set with
+/// [`ConfigBuilder::inspect`](crate::ConfigBuilder::inspect).
+///
+/// Bodies are borrowed as they were sent and received, except that secrets such as
+/// `client_secret` are redacted from the response body.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct RequestInspection<'a> {
+    /// The HTTP method of the request.
+    pub method: &'a Method,
+
+    /// The full URL of the request.
+    pub url: &'a str,
+
+    /// The encoded request body, if the request had one.
+    pub request_body: Option<&'a str>,
+
+    /// The HTTP status code of the response.
+    pub status: u16,
+
+    /// The response body, with secrets redacted.
+    pub response_body: &'a str,
+}
+
+/// Callback receiving every [`RequestInspection`].
+pub(crate) type InspectFn = Arc<dyn Fn(&RequestInspection<'_>) + Send + Sync>;
+
+/// Wraps an [`InspectFn`] so [`Config`] can stay `Debug`.
+#[derive(Clone)]
+pub(crate) struct Inspector(pub(crate) InspectFn);
+
+impl fmt::Debug for Inspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Inspector(..)")
+    }
+}
+
+/// The parts of a sent request that are reported to the [`Inspector`] or decide how it is
+/// retried.
+struct SentRequest<'a> {
+    method: &'a Method,
+    url: &'a str,
+    body: Option<&'a str>,
+    /// The idempotency key sent with the request, if any.
+    idempotency_key: Option<&'a str>,
+    /// Whether error responses such as `500` or `429` and timeouts may be retried. PayRex may
+    /// already have applied a request that failed this way, so only requests that are safe to
+    /// repeat are retried: `GET` and `DELETE`, and requests carrying an idempotency key.
+    repeatable: bool,
+}
+
+/// Returns `true` if a `409 Conflict` response is about a reused idempotency key rather than,
+/// say, an invalid state transition.
+///
+/// That is the case when an error code says so, or when the request carried a key and the body
+/// names no other cause.
+fn is_idempotency_conflict(sent: &SentRequest<'_>, errors: &[FieldError]) -> bool {
+    errors
+        .iter()
+        .any(|error| error.code.contains("idempotency"))
+        || (sent.idempotency_key.is_some() && errors.iter().all(|error| error.code.is_empty()))
+}
+
+/// The `expand[]` parameter asking PayRex to embed related resources in a response.
+///
+/// Form bodies repeat `expand[]` once per field; JSON bodies send an `expand` array.
+struct Expand<'a> {
+    encoding: BodyEncoding,
+    fields: &'a [&'a str],
+}
+
+impl Serialize for Expand<'_> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        match self.encoding {
+            BodyEncoding::Form => {
+                for field in self.fields {
+                    map.serialize_entry("expand[]", field)?;
+                }
+            }
+            BodyEncoding::Json => map.serialize_entry("expand", self.fields)?,
+        }
+        map.end()
+    }
+}
+
+/// Returns `true` for failures after which PayRex may already have applied the request: error
+/// responses and timeouts. Connection failures are the only ones where it never arrived.
+fn may_have_been_applied(error: &Error) -> bool {
+    match error {
+        Error::Api { .. } | Error::RateLimit { .. } | Error::Timeout(_) => true,
+        Error::Http(e) => e.is_timeout(),
+        _ => false,
+    }
+}
+
+/// Replaces the values of [`REDACTED_FIELDS`] anywhere in a JSON `body`.
+///
+/// Borrows `body` unchanged when it contains none of them.
+fn redact(body: &str) -> Cow<'_, str> {
+    fn redact_value(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    if REDACTED_FIELDS.contains(&key.as_str()) && value.is_string() {
+                        *value = "[REDACTED]".into();
+                    } else {
+                        redact_value(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(redact_value),
+            _ => {}
+        }
+    }
+
+    if !REDACTED_FIELDS.iter().any(|field| body.contains(field)) {
+        return Cow::Borrowed(body);
+    }
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            Cow::Owned(value.to_string())
+        }
+        Err(_) => Cow::Borrowed(body),
+    }
+}
+
+/// Returns at most `max_len` bytes of a response `body` for an error message, cut at a
+/// character boundary and with secrets redacted.
+///
+/// A body too malformed to redact is cut before the first of [`REDACTED_FIELDS`] instead.
+/// A `max_len` of 0 leaves the body out and returns an empty string.
+fn snippet(body: &[u8], max_len: usize) -> String {
+    if max_len == 0 {
+        return String::new();
+    }
+
+    let text = String::from_utf8_lossy(body);
+    let redacted = redact(&text);
+    let mut end = match &redacted {
+        Cow::Borrowed(text) => REDACTED_FIELDS
+            .iter()
+            .filter_map(|field| text.find(field))
+            .min()
+            .unwrap_or(text.len()),
+        Cow::Owned(text) => text.len(),
+    }
+    .min(max_len);
+    while !redacted.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    if end < redacted.len() {
+        format!("{}...", &redacted[..end])
+    } else {
+        redacted.into_owned()
+    }
+}
 
 /// HTTP client for making requests to the PayRex API.
 pub(crate) struct HttpClient {
     client: ReqwestClient,
     config: Config,
+    retry_policy: Arc<dyn RetryPolicy>,
+    /// Limits in-flight requests when [`Config::max_concurrent_requests`] is set. Lives here so
+    /// every clone of a [`Client`](crate::Client) shares it.
+    concurrency: Option<Semaphore>,
 }
 
 impl HttpClient {
     pub fn new(config: Config) -> Result<Self> {
-        let mut headers = header::HeaderMap::new();
+        let mut headers = config.default_headers().clone();
 
         let credentials = format!("{}:", config.api_key());
         let encoded = general_purpose::STANDARD.encode(credentials.as_bytes());
@@ -39,18 +255,45 @@ impl HttpClient {
             header::HeaderValue::from_static("application/x-www-form-urlencoded"),
         );
 
-        let client = ReqwestClient::builder()
+        let mut builder = ReqwestClient::builder()
             .default_headers(headers)
-            .timeout(config.timeout())
+            .timeout(config.timeout());
+
+        if let Some(connect_timeout) = config.connect_timeout() {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+
+        let client = builder
             .build()
             .map_err(|e| Error::Config(format!("Failed to build HTTP client: {e}")))?;
 
-        Ok(Self { client, config })
+        let retry_policy = config.retry_policy();
+        let concurrency = config.max_concurrent_requests().map(Semaphore::new);
+
+        Ok(Self {
+            client,
+            config,
+            retry_policy,
+            concurrency,
+        })
+    }
+
+    pub const fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Merges the configured default metadata into the `metadata` of a create request.
+    pub(crate) fn apply_default_metadata(&self, metadata: &mut Option<Metadata>) {
+        let defaults = self.config.default_metadata();
+        if !defaults.is_empty() {
+            metadata
+                .get_or_insert_with(Metadata::new)
+                .merge_defaults(defaults);
+        }
     }
 
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let url = self.build_url(path)?;
-        self.execute_with_retry(|| self.client.get(&url)).await
+        self.request(Method::GET, path, None::<&()>).await
     }
 
     pub async fn get_with_params<B: Serialize, T: DeserializeOwned>(
@@ -58,28 +301,42 @@ impl HttpClient {
         path: &str,
         body: &B,
     ) -> Result<T> {
-        let url = self.build_url(path)?;
-        let form_data = serde_qs::to_string(body)
-            .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}")))?;
-        self.execute_with_retry(|| self.client.get(&url).body(form_data.clone()))
-            .await
+        self.request(Method::GET, path, Some(body)).await
+    }
+
+    /// Sends a `GET` request asking PayRex to embed the related resources named in `fields`.
+    pub async fn get_expanded<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        fields: &[&str],
+    ) -> Result<T> {
+        let expand = Expand {
+            encoding: self.config.body_encoding(),
+            fields,
+        };
+        self.request(Method::GET, path, Some(&expand)).await
     }
 
     pub async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
-        let url = self.build_url(path)?;
-        let form_data = serde_qs::to_string(body)
-            .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}")))?;
-        self.execute_with_retry(|| self.client.post(&url).body(form_data.clone()))
+        self.request(Method::POST, path, Some(body)).await
+    }
+
+    /// Sends a `POST` request tagged with an idempotency key.
+    ///
+    /// The same key is sent on every retry attempt, and callers that re-issue a request after
+    /// dropping an earlier future must reuse the key so PayRex can deduplicate them.
+    pub async fn post_idempotent<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+        idempotency_key: &str,
+    ) -> Result<T> {
+        self.dispatch(Method::POST, path, Some(body), Some(idempotency_key))
             .await
     }
 
-    #[allow(dead_code)]
     pub async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
-        let url = self.build_url(path)?;
-        let form_data = serde_qs::to_string(body)
-            .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}")))?;
-        self.execute_with_retry(|| self.client.put(&url).body(form_data.clone()))
-            .await
+        self.request(Method::PUT, path, Some(body)).await
     }
 
     pub async fn patch<B: Serialize, T: DeserializeOwned>(
@@ -87,16 +344,86 @@ impl HttpClient {
         path: &str,
         body: &B,
     ) -> Result<T> {
-        let url = self.build_url(path)?;
-        let form_data = serde_qs::to_string(body)
-            .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}")))?;
-        self.execute_with_retry(|| self.client.patch(&url).body(form_data.clone()))
-            .await
+        self.request(Method::PATCH, path, Some(body)).await
     }
 
     pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.request(Method::DELETE, path, None::<&()>).await
+    }
+
+    /// Sends a request with the given method, encoding `body` when present according to
+    /// [`Config::body_encoding`].
+    ///
+    /// Every verb-specific helper goes through here, so cross-cutting behavior only needs to be
+    /// added in one place.
+    pub async fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        self.dispatch(method, path, body, None).await
+    }
+
+    async fn dispatch<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        idempotency_key: Option<&str>,
+    ) -> Result<T> {
+        let scoped_key = if idempotency_key.is_none() && method == Method::POST {
+            SCOPED_IDEMPOTENCY_KEY
+                .try_with(ScopedIdempotencyKey::next)
+                .ok()
+        } else {
+            None
+        };
+        let idempotency_key = idempotency_key.or(scoped_key.as_deref());
+
         let url = self.build_url(path)?;
-        self.execute_with_retry(|| self.client.delete(&url)).await
+        let encoding = self.config.body_encoding();
+        let payload = body
+            .map(|body| match encoding {
+                BodyEncoding::Form => serde_qs::to_string(body)
+                    .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}"))),
+                BodyEncoding::Json => serde_json::to_string(body)
+                    .map_err(|e| Error::Config(format!("Failed to serialize request body: {e}"))),
+            })
+            .transpose()?;
+
+        let sent = SentRequest {
+            method: &method,
+            url: &url,
+            body: payload.as_deref(),
+            idempotency_key,
+            repeatable: matches!(method, Method::GET | Method::DELETE) || idempotency_key.is_some(),
+        };
+        self.execute_with_retry(&sent, || {
+            let mut request = self.client.request(method.clone(), &url);
+            if let Some(key) = idempotency_key {
+                request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+            }
+            if let Some(payload) = &payload {
+                if encoding == BodyEncoding::Json {
+                    request = request.header(header::CONTENT_TYPE, "application/json");
+                }
+                request = request.body(payload.clone());
+            }
+            request
+        })
+        .await
+        .map_err(|error| match (idempotency_key, error) {
+            (
+                Some(key),
+                Error::Api {
+                    kind: ErrorKind::Idempotency,
+                    message,
+                    ..
+                },
+            ) => Error::idempotency(key, message),
+            (_, error) => error,
+        })
     }
 
     fn build_url(&self, path: &str) -> Result<String> {
@@ -105,23 +432,52 @@ impl HttpClient {
         Ok(format!("{base}/{path}"))
     }
 
-    async fn execute_with_retry<F, T>(&self, request_builder: F) -> Result<T>
+    /// Sends the request built by `request_builder`, retrying retryable failures.
+    ///
+    /// # Cancellation
+    ///
+    /// Dropping the returned future is safe at any await point: no state is kept between calls,
+    /// so a dropped future either never sent the request, or sent it and abandoned the response.
+    /// In the latter case the request may still have been applied by PayRex. Non-idempotent
+    /// requests should therefore carry an idempotency key (see [`Self::post_idempotent`]) and be
+    /// re-issued with the same key.
+    async fn execute_with_retry<F, T>(
+        &self,
+        sent: &SentRequest<'_>,
+        request_builder: F,
+    ) -> Result<T>
     where
         F: Fn() -> RequestBuilder,
         T: DeserializeOwned,
     {
         let mut attempts = 0;
-        let max_retries = self.config.max_retries();
 
         loop {
             let request = request_builder();
 
-            match self.execute_request(request).await {
-                Ok(response) => return self.handle_response(response).await,
-                Err(e) if e.is_retryable() && attempts < max_retries => {
+            let permit = match &self.concurrency {
+                Some(semaphore) => Some(
+                    semaphore
+                        .acquire()
+                        .await
+                        .map_err(|e| Error::internal(e.to_string()))?,
+                ),
+                None => None,
+            };
+            let result = match self.execute_request(request).await {
+                Ok(response) => self.handle_response(response, sent).await,
+                Err(e) => Err(e),
+            };
+            drop(permit);
+
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && (sent.repeatable || !may_have_been_applied(&e)) => {
                     attempts += 1;
-                    let delay = self.calculate_retry_delay(attempts);
-                    tokio::time::sleep(delay).await;
+                    match self.retry_policy.delay(attempts, &e) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => return Err(e),
+                    }
                 }
                 Err(e) => return Err(e),
             }
@@ -138,38 +494,103 @@ impl HttpClient {
         })
     }
 
-    async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T> {
+    async fn handle_response<T: DeserializeOwned>(
+        &self,
+        response: Response,
+        sent: &SentRequest<'_>,
+    ) -> Result<T> {
         let status = response.status();
         let request_id = response
             .headers()
             .get("x-request-id")
             .and_then(|v| v.to_str().ok())
             .map(String::from);
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs);
 
-        if status == StatusCode::TOO_MANY_REQUESTS {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .map(Duration::from_secs);
+        let inspector = self.config.inspector.as_ref();
+        let path = response.url().path().to_string();
+        let body = if status.is_success() {
+            response.bytes().await?
+        } else {
+            response.bytes().await.unwrap_or_default()
+        };
 
+        if let Some(Inspector(inspect)) = inspector {
+            inspect(&RequestInspection {
+                method: sent.method,
+                url: sent.url,
+                request_body: sent.body,
+                status: status.as_u16(),
+                response_body: &redact(&String::from_utf8_lossy(&body)),
+            });
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
             return Err(Error::RateLimit { retry_after });
         }
 
         if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            let kind = Self::status_to_error_kind(status);
+            let error_body = String::from_utf8_lossy(&body).into_owned();
+            let errors = FieldError::parse_all(&error_body);
+            let kind = if status == StatusCode::CONFLICT && is_idempotency_conflict(sent, &errors) {
+                ErrorKind::Idempotency
+            } else {
+                Self::status_to_error_kind(status)
+            };
 
             return Err(Error::Api {
                 kind,
+                error_code: errors
+                    .first()
+                    .map(|error| error.code.as_str())
+                    .filter(|code| !code.is_empty())
+                    .map(str::to_string),
+                errors,
                 message: error_body,
                 status_code: Some(status.as_u16()),
                 request_id,
             });
         }
 
-        response.json().await.map_err(Error::Http)
+        if self.config.warn_on_livemode_mismatch()
+            && let Ok(value) = serde_json::from_slice::<serde_json::Value>(&body)
+        {
+            self.check_livemode(&path, &value);
+        }
+        serde_json::from_slice(&body).map_err(|source| Error::Deserialization {
+            body_snippet: snippet(&body, self.config.error_body_snippet_len()),
+            path,
+            source,
+        })
+    }
+
+    /// Warns if the `livemode` of a resource, or of any resource in a list, does not match the
+    /// mode of the client.
+    fn check_livemode(&self, path: &str, value: &serde_json::Value) {
+        let expected = !self.config.is_test_mode();
+        let resources = match value.get("data").and_then(serde_json::Value::as_array) {
+            Some(items) => items.iter().collect(),
+            None => vec![value],
+        };
+        let mismatched = resources.iter().any(|resource| {
+            resource
+                .get("livemode")
+                .and_then(crate::types::lenient_bool::from_value)
+                .is_some_and(|livemode| livemode != expected)
+        });
+
+        if mismatched {
+            tracing::warn!(
+                path,
+                test_mode = self.config.is_test_mode(),
+                "PayRex response livemode does not match the client's mode"
+            );
+        }
     }
 
     fn status_to_error_kind(status: StatusCode) -> ErrorKind {
@@ -178,26 +599,22 @@ impl HttpClient {
             StatusCode::UNAUTHORIZED => ErrorKind::Authentication,
             StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
             StatusCode::NOT_FOUND => ErrorKind::NotFound,
+            StatusCode::CONFLICT => ErrorKind::InvalidRequest,
             StatusCode::TOO_MANY_REQUESTS => ErrorKind::RateLimit,
             s if s.is_server_error() => ErrorKind::ServerError,
             _ => ErrorKind::Unknown,
         }
     }
-
-    fn calculate_retry_delay(&self, attempt: u32) -> Duration {
-        let base_delay = self.config.retry_delay();
-        let multiplier = 2_u32.pow(attempt.saturating_sub(1));
-        base_delay * multiplier
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::mock_config;
 
     #[test]
     fn test_build_url() {
-        let config = Config::new("test_key").unwrap();
+        let config = Config::new("sk_test_key").unwrap();
         let client = HttpClient::new(config).unwrap();
 
         let url = client.build_url("/payment_intents").unwrap();
@@ -205,18 +622,784 @@ mod tests {
         assert!(url.starts_with("https://"));
     }
 
+    #[test]
+    fn test_timeouts_propagate() {
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .timeout(Duration::from_secs(120))
+            .connect_timeout(Duration::from_secs(3))
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+
+        assert_eq!(client.config.timeout(), Duration::from_secs(120));
+        assert_eq!(
+            client.config.connect_timeout(),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_response_times_out() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let error = client
+            .get::<serde_json::Value>("/customers")
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, Error::Timeout(d) if d == Duration::from_millis(50)),
+            "{error:?}"
+        );
+    }
+
     #[test]
     fn test_calculate_retry_delay() {
         let config = Config::builder()
-            .api_key("test_key")
+            .api_key("sk_test_key")
             .retry_delay(Duration::from_millis(100))
             .build()
             .unwrap();
         let client = HttpClient::new(config).unwrap();
+        let error = Error::Timeout(Duration::from_secs(1));
+
+        assert_eq!(
+            client.retry_policy.delay(1, &error),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            client.retry_policy.delay(2, &error),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            client.retry_policy.delay(3, &error),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(client.retry_policy.delay(4, &error), None);
+    }
+
+    #[tokio::test]
+    async fn test_each_verb_goes_through_request() {
+        use wiremock::matchers::{any, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[derive(Serialize)]
+        struct Body {
+            name: &'static str,
+        }
+
+        let server = MockServer::start().await;
+        for verb in ["GET", "POST", "PUT", "PATCH", "DELETE"] {
+            Mock::given(method(verb))
+                .and(path(format!("/{}", verb.to_lowercase())))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "method": verb,
+                })))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .api_base_url(server.uri())
+            .max_retries(0)
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let body = Body { name: "x" };
+
+        let get: serde_json::Value = client.get("/get").await.unwrap();
+        assert_eq!(get["method"], "GET");
+        let post: serde_json::Value = client.post("/post", &body).await.unwrap();
+        assert_eq!(post["method"], "POST");
+        let put: serde_json::Value = client.put("/put", &body).await.unwrap();
+        assert_eq!(put["method"], "PUT");
+        let patch: serde_json::Value = client.patch("/patch", &body).await.unwrap();
+        assert_eq!(patch["method"], "PATCH");
+        let delete: serde_json::Value = client.delete("/delete").await.unwrap();
+        assert_eq!(delete["method"], "DELETE");
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 5);
+        assert!(requests[0].body.is_empty());
+        for request in &requests[1..4] {
+            assert_eq!(request.body, b"name=x");
+        }
+        assert!(requests[4].body.is_empty());
+    }
 
-        assert_eq!(client.calculate_retry_delay(1), Duration::from_millis(100));
-        assert_eq!(client.calculate_retry_delay(2), Duration::from_millis(200));
-        assert_eq!(client.calculate_retry_delay(3), Duration::from_millis(400));
+    #[derive(Debug)]
+    struct ConstantBackoff {
+        delay: Duration,
+        max_retries: u32,
+    }
+
+    impl RetryPolicy for ConstantBackoff {
+        fn delay(&self, attempt: u32, _error: &Error) -> Option<Duration> {
+            (attempt <= self.max_retries).then_some(self.delay)
+        }
+    }
+
+    #[derive(Debug)]
+    struct StopAfterOne;
+
+    impl RetryPolicy for StopAfterOne {
+        fn delay(&self, attempt: u32, _error: &Error) -> Option<Duration> {
+            (attempt == 1).then_some(Duration::ZERO)
+        }
+    }
+
+    async fn client_with_policy(
+        server: &wiremock::MockServer,
+        policy: Box<dyn RetryPolicy>,
+    ) -> HttpClient {
+        let config = Config::builder()
+            .api_key("sk_test_key")
+            .api_base_url(server.uri())
+            .retry_policy(policy)
+            .build()
+            .unwrap();
+        HttpClient::new(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_constant_retry_policy() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let policy = ConstantBackoff {
+            delay: Duration::from_millis(1),
+            max_retries: 5,
+        };
+        let client = client_with_policy(&server, Box::new(policy)).await;
+        let result: Result<serde_json::Value> = client.get("/payments/pay_123").await;
+
+        assert!(result.is_ok());
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stop_after_one_retry_policy() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let client = client_with_policy(&server, Box::new(StopAfterOne)).await;
+        let result: Result<serde_json::Value> = client.get("/payments/pay_123").await;
+
+        assert_eq!(result.unwrap_err().status_code(), Some(503));
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_error_responses_retried_only_with_idempotency_key() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let policy = ConstantBackoff {
+            delay: Duration::from_millis(1),
+            max_retries: 2,
+        };
+        let client = client_with_policy(&server, Box::new(policy)).await;
+
+        let result: Result<serde_json::Value> = client.post("/payment_intents", &()).await;
+        assert_eq!(result.unwrap_err().status_code(), Some(500));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        let result: Result<serde_json::Value> = client
+            .post_idempotent("/payment_intents", &(), "order-1")
+            .await;
+        assert_eq!(result.unwrap_err().status_code(), Some(500));
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_post_timeouts_retried_only_with_idempotency_key() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .timeout(Duration::from_millis(50))
+            .retry_policy(Box::new(ConstantBackoff {
+                delay: Duration::from_millis(1),
+                max_retries: 2,
+            }))
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+
+        let result: Result<serde_json::Value> = client.post("/payment_intents", &()).await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        let result: Result<serde_json::Value> = client
+            .post_idempotent("/payment_intents", &(), "order-1")
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_default_headers_are_sent() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("x-tenant-id", "tenant_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .header("X-Tenant-Id", "tenant_1")
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let _: serde_json::Value = client.get("/customers/cus_123").await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(
+            requests[0]
+                .headers
+                .get("authorization")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("Basic ")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_body_encoding() {
+        use wiremock::matchers::{body_json, header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(serde_json::json!({
+                "amount": 1000,
+                "line_items": [{ "description": "Item", "quantity": 2 }],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .body_encoding(BodyEncoding::Json)
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let body = serde_json::json!({
+            "amount": 1000,
+            "line_items": [{ "description": "Item", "quantity": 2 }],
+        });
+        let _: serde_json::Value = client.post("/checkout_sessions", &body).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(
+            requests[0].headers.get("content-type").unwrap(),
+            "application/json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_conflict_carries_key() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(IDEMPOTENCY_KEY_HEADER, "order-42"))
+            .respond_with(ResponseTemplate::new(409).set_body_string("idempotency key reused"))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server).build().unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let body = std::collections::HashMap::from([("amount", "1000")]);
+        let result: Result<serde_json::Value> = client
+            .post_idempotent("/payment_intents", &body, "order-42")
+            .await;
+
+        match result.unwrap_err() {
+            Error::Idempotency { key, message } => {
+                assert_eq!(key, "order-42");
+                assert_eq!(message, "idempotency key reused");
+            }
+            other => panic!("expected idempotency error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflict_without_idempotency_is_invalid_request() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let state_error = serde_json::json!({
+            "errors": [{ "code": "resource_invalid_state", "detail": "already captured" }],
+        });
+        Mock::given(method("POST"))
+            .and(path("/payment_intents/pi_123/capture"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(state_error))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/payment_intents"))
+            .and(header(IDEMPOTENCY_KEY_HEADER, "order-42"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(serde_json::json!({
+                "errors": [{ "code": "idempotency_key_in_use", "detail": "in use" }],
+            })))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server).build().unwrap();
+        let client = HttpClient::new(config).unwrap();
+
+        let error = client
+            .post::<_, serde_json::Value>("/payment_intents/pi_123/capture", &())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Api {
+                kind: ErrorKind::InvalidRequest,
+                status_code: Some(409),
+                ..
+            }
+        ));
+
+        let error = client
+            .post_idempotent::<_, serde_json::Value>("/payment_intents/pi_123/capture", &(), "k")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Api {
+                kind: ErrorKind::InvalidRequest,
+                ..
+            }
+        ));
+
+        let error = client
+            .post_idempotent::<_, serde_json::Value>("/payment_intents", &(), "order-42")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Idempotency { key, .. } if key == "order-42"));
+    }
+
+    #[tokio::test]
+    async fn test_error_response_carries_field_errors() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "errors": [
+                    { "code": "parameter_required", "detail": "amount is required", "parameter": "amount" },
+                    { "code": "parameter_invalid", "detail": "email is invalid", "parameter": "email" },
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server).build().unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let error = client
+            .post::<_, serde_json::Value>("/customers", &())
+            .await
+            .unwrap_err();
+
+        let params: Vec<_> = error
+            .validation_errors()
+            .unwrap()
+            .iter()
+            .map(|e| e.param.as_deref().unwrap())
+            .collect();
+        assert_eq!(params, ["amount", "email"]);
+        assert_eq!(error.status_code(), Some(400));
+    }
+
+    #[tokio::test]
+    async fn test_inspect_hook_receives_request_details() {
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/payment_intents"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "pi_123",
+                "client_secret": "pi_123_secret_abc",
+                "nested": [{ "secret_key": "whsk_abc" }],
+            })))
+            .mount(&server)
+            .await;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook_seen = Arc::clone(&seen);
+        let config = mock_config(&server)
+            .inspect(Arc::new(move |inspection: &RequestInspection<'_>| {
+                hook_seen.lock().unwrap().push((
+                    inspection.method.clone(),
+                    inspection.url.to_string(),
+                    inspection.request_body.map(String::from),
+                    inspection.status,
+                    inspection.response_body.to_string(),
+                ));
+            }))
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let body: serde_json::Value = client
+            .post("/payment_intents", &serde_json::json!({ "amount": 10000 }))
+            .await
+            .unwrap();
+        assert_eq!(body["client_secret"], "pi_123_secret_abc");
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        let (method, url, request_body, status, response_body) = &seen[0];
+        assert_eq!(*method, Method::POST);
+        assert_eq!(*url, format!("{}/payment_intents", server.uri()));
+        assert_eq!(request_body.as_deref(), Some("amount=10000"));
+        assert_eq!(*status, 200);
+        assert!(response_body.contains("pi_123"));
+        assert!(!response_body.contains("pi_123_secret_abc"));
+        assert!(!response_body.contains("whsk_abc"));
+    }
+
+    #[test]
+    fn test_redact_borrows_bodies_without_secrets() {
+        assert!(matches!(redact(r#"{"id":"pi_123"}"#), Cow::Borrowed(_)));
+        assert_eq!(
+            redact(r#"{"client_secret":"abc"}"#),
+            r#"{"client_secret":"[REDACTED]"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_error_includes_path_and_snippet() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/payment_intents/pi_123"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"id": "pi_123", "amount": 10"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/payment_intents/pi_long"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<html>".repeat(20)))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .error_body_snippet_len(30)
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+
+        let error = client
+            .get::<serde_json::Value>("/payment_intents/pi_123")
+            .await
+            .unwrap_err();
+        let Error::Deserialization {
+            path, body_snippet, ..
+        } = &error
+        else {
+            panic!("expected a deserialization error, got {error:?}");
+        };
+        assert_eq!(path, "/payment_intents/pi_123");
+        assert_eq!(body_snippet, r#"{"id": "pi_123", "amount": 10"#);
+        assert!(error.to_string().contains("/payment_intents/pi_123"));
+
+        let error = client
+            .get::<serde_json::Value>("/payment_intents/pi_long")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Deserialization { body_snippet, .. }
+                if body_snippet == format!("{}...", &"<html>".repeat(5))
+        ));
+    }
+
+    #[test]
+    fn test_snippet_truncates_and_redacts() {
+        assert_eq!(snippet(b"abcdef", 10), "abcdef");
+        assert_eq!(snippet(b"abcdef", 3), "abc...");
+        assert_eq!(snippet("₱₱".as_bytes(), 4), "₱...");
+        assert_eq!(snippet(b"abc", 0), "");
+        assert_eq!(
+            snippet(br#"{"client_secret":"abc"}"#, 100),
+            r#"{"client_secret":"[REDACTED]"}"#
+        );
+        assert_eq!(
+            snippet(br#"{"id":1,"client_secret":"abc""#, 100),
+            r#"{"id":1,"..."#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_requests() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+        const LIMIT: usize = 2;
+
+        /// Records the most requests seen in flight, i.e. received but not yet completed.
+        struct InFlight {
+            received: Arc<AtomicUsize>,
+            completed: Arc<AtomicUsize>,
+            max: Arc<AtomicUsize>,
+        }
+
+        impl Respond for InFlight {
+            fn respond(&self, _: &Request) -> ResponseTemplate {
+                let received = self.received.fetch_add(1, Ordering::SeqCst) + 1;
+                let in_flight = received - self.completed.load(Ordering::SeqCst);
+                self.max.fetch_max(in_flight, Ordering::SeqCst);
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({}))
+                    .set_delay(Duration::from_millis(50))
+            }
+        }
+
+        let received = Arc::new(AtomicUsize::new(0));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let max = Arc::new(AtomicUsize::new(0));
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(InFlight {
+                received: Arc::clone(&received),
+                completed: Arc::clone(&completed),
+                max: Arc::clone(&max),
+            })
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .max_concurrent_requests(LIMIT)
+            .build()
+            .unwrap();
+        let client = Arc::new(HttpClient::new(config).unwrap());
+
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let client = Arc::clone(&client);
+                let completed = Arc::clone(&completed);
+                tokio::spawn(async move {
+                    client
+                        .get::<serde_json::Value>("/payment_intents")
+                        .await
+                        .unwrap();
+                    completed.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(received.load(Ordering::SeqCst), 6);
+        assert_eq!(max.load(Ordering::SeqCst), LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_error_response_keeps_error_code() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "errors": [
+                    { "code": "parameter_invalid_empty", "detail": "name cannot be empty", "parameter": "name" },
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server).build().unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let error = client
+            .post::<_, serde_json::Value>("/customers", &())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::Api {
+                kind: ErrorKind::InvalidRequest,
+                ..
+            }
+        ));
+        assert_eq!(error.error_code(), Some("parameter_invalid_empty"));
+        assert_eq!(Error::invalid_request("x").error_code(), None);
+    }
+
+    #[tokio::test]
+    async fn test_error_response_without_code_has_no_error_code() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "errors": [{ "detail": "name cannot be empty", "parameter": "name" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server).build().unwrap();
+        let client = HttpClient::new(config).unwrap();
+        let error = client
+            .post::<_, serde_json::Value>("/customers", &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.error_code(), None);
+        assert_eq!(
+            error.validation_errors().unwrap()[0].message,
+            "name cannot be empty"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_livemode_mismatch_is_logged() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/customers/cus_live"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "id": "cus_live", "livemode": true })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/customers/cus_test"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "id": "cus_test", "livemode": false })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/customers"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "livemode": false }, { "livemode": true }],
+                "has_more": false,
+            })))
+            .mount(&server)
+            .await;
+
+        let captured = crate::test_utils::CapturedLogs::start();
+
+        let client = |warn| {
+            let config = mock_config(&server)
+                .warn_on_livemode_mismatch(warn)
+                .build()
+                .unwrap();
+            HttpClient::new(config).unwrap()
+        };
+
+        let _: serde_json::Value = client(true).get("/customers/cus_test").await.unwrap();
+        let _: serde_json::Value = client(false).get("/customers/cus_live").await.unwrap();
+        assert!(captured.contents().is_empty());
+
+        let value: serde_json::Value = client(true).get("/customers/cus_live").await.unwrap();
+        assert_eq!(value["id"], "cus_live");
+        assert!(captured.contents().contains("livemode does not match"));
+        assert!(captured.contents().contains("/customers/cus_live"));
+
+        let _: serde_json::Value = client(true).get("/customers").await.unwrap();
+        assert_eq!(
+            captured
+                .contents()
+                .matches("livemode does not match")
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_livemode_mismatch_is_logged_for_lenient_booleans() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "livemode": 0 }, { "livemode": "false" }, { "livemode": 1 }],
+                "has_more": false,
+            })))
+            .mount(&server)
+            .await;
+
+        let captured = crate::test_utils::CapturedLogs::start();
+        let config = mock_config(&server)
+            .warn_on_livemode_mismatch(true)
+            .build()
+            .unwrap();
+        let client = HttpClient::new(config).unwrap();
+
+        let _: serde_json::Value = client.get("/customers").await.unwrap();
+        assert!(captured.contents().contains("livemode does not match"));
     }
 
     #[test]
@@ -233,5 +1416,9 @@ mod tests {
             HttpClient::status_to_error_kind(StatusCode::NOT_FOUND),
             ErrorKind::NotFound
         );
+        assert_eq!(
+            HttpClient::status_to_error_kind(StatusCode::CONFLICT),
+            ErrorKind::InvalidRequest
+        );
     }
 }
@@ -14,6 +14,7 @@ mod client;
 mod config;
 mod error;
 mod http;
+mod retry;
 
 // Type modules
 pub mod types;
@@ -21,10 +22,18 @@ pub mod types;
 // Resource modules
 pub mod resources;
 
+// Webhook verification
+pub mod webhook;
+
+#[cfg(test)]
+mod test_utils;
+
 // Re-exports
-pub use client::Client;
-pub use config::{Config, ConfigBuilder};
-pub use error::{Error, ErrorKind, Result};
+pub use client::{Client, ClientBuilder};
+pub use config::{BodyEncoding, Config, ConfigBuilder};
+pub use error::{Error, ErrorKind, FieldError, Result};
+pub use http::RequestInspection;
+pub use retry::{ExponentialBackoff, RetryPolicy};
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
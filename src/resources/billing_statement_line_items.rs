@@ -7,8 +7,12 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
+    resources::{
+        billing_statements::{BillingStatement, BillingStatementStatus},
+        paths,
+    },
     types::{BillingStatementId, BillingStatementLineItemId, Timestamp},
 };
 
@@ -28,7 +32,7 @@ impl BillingStatementLineItems {
         params: CreateBillingStatementLineItem,
     ) -> Result<BillingStatementLineItem> {
         self.http
-            .post("/billing_statement_line_items", &params)
+            .post(paths::collection::<BillingStatementLineItemId>(), &params)
             .await
     }
 
@@ -37,18 +41,56 @@ impl BillingStatementLineItems {
         id: BillingStatementLineItemId,
         params: UpdateBillingStatementLineItem,
     ) -> Result<BillingStatementLineItem> {
-        self.http
-            .put(
-                &format!("/billing_statement_line_items/{}", id.as_str()),
-                &params,
-            )
-            .await
+        self.http.put(&paths::resource(&id), &params).await
     }
 
     pub async fn delete(&self, id: &BillingStatementLineItemId) -> Result<()> {
-        self.http
-            .delete(&format!("/billing_statement_line_items/{}", id.as_str()))
-            .await
+        self.http.delete(&paths::resource(id)).await
+    }
+
+    /// Replaces every line item of a draft billing statement with `items`, returning the new
+    /// line items.
+    ///
+    /// PayRex has no bulk endpoint, so this retrieves the statement, deletes its line items one
+    /// by one, then creates `items` one by one. **This is not atomic**: if a call fails, the
+    /// error is returned and the statement is left with whatever was deleted or created up to
+    /// that point. The `billing_statement_id` of each item is set to `statement_id`.
+    ///
+    /// Returns [`Error::InvalidRequest`] before changing anything if the statement is not a
+    /// draft, or if PayRex did not return its line items.
+    pub async fn replace_all(
+        &self,
+        statement_id: &BillingStatementId,
+        items: Vec<CreateBillingStatementLineItem>,
+    ) -> Result<Vec<BillingStatementLineItem>> {
+        let statement: BillingStatement = self.http.get(&paths::resource(statement_id)).await?;
+
+        if statement.status != BillingStatementStatus::Draft {
+            return Err(Error::invalid_request(format!(
+                "billing statement {} is {:?}; only draft statements can have their line items \
+                 replaced",
+                statement_id.as_str(),
+                statement.status
+            )));
+        }
+        let existing = statement.line_items.ok_or_else(|| {
+            Error::invalid_request(format!(
+                "billing statement {} was returned without its line items, so they cannot be \
+                 replaced safely",
+                statement_id.as_str()
+            ))
+        })?;
+
+        for item in existing {
+            self.delete(&item.id).await?;
+        }
+
+        let mut created = Vec::with_capacity(items.len());
+        for mut item in items {
+            item.billing_statement_id = statement_id.clone();
+            created.push(self.create(item).await?);
+        }
+        Ok(created)
     }
 }
 
@@ -60,6 +102,7 @@ pub struct BillingStatementLineItem {
     pub unit_price: u64,
     pub quantity: u64,
     pub billing_statement_id: BillingStatementId,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
@@ -75,8 +118,11 @@ pub struct CreateBillingStatementLineItem {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct UpdateBillingStatementLineItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub unit_price: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub quantity: Option<u64>,
 }
 
@@ -122,6 +168,7 @@ impl UpdateBillingStatementLineItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::{BillingStatementId, BillingStatementLineItemId, Timestamp};
     use serde_json;
 
@@ -162,6 +209,8 @@ mod tests {
             created_at: Timestamp::from_unix(1_621_000_000),
             updated_at: Timestamp::from_unix(1_621_000_100),
         };
+        assert_round_trip(&item);
+
         let json = serde_json::to_value(&item).unwrap();
         assert_eq!(json["id"], "bstm_li_1");
         assert_eq!(json["description"], "Test item");
@@ -173,6 +222,151 @@ mod tests {
         assert_eq!(json["updated_at"], 1_621_000_100);
     }
 
+    #[tokio::test]
+    async fn test_replace_all_deletes_then_creates() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let line_item = |id: &str, description: &str| {
+            serde_json::json!({
+                "id": id,
+                "description": description,
+                "unit_price": 1500,
+                "quantity": 2,
+                "billing_statement_id": "bstm_123",
+                "livemode": false,
+                "created_at": 1_621_000_000,
+                "updated_at": 1_621_000_000,
+            })
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/billing_statements/bstm_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "bstm_123",
+                "amount": 6000,
+                "currency": "PHP",
+                "customer_id": "cus_123",
+                "livemode": false,
+                "status": "draft",
+                "line_items": [line_item("bstm_li_1", "Old A"), line_item("bstm_li_2", "Old B")],
+                "payment_settings": { "payment_methods": ["card"] },
+                "created_at": 1_621_000_000,
+                "updated_at": 1_621_000_000,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::Value::Null))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/billing_statement_line_items"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(line_item("bstm_li_3", "New")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let items = vec![CreateBillingStatementLineItem::new(
+            BillingStatementId::new("bstm_other"),
+            "New",
+            1500,
+            2,
+        )];
+        let created = client
+            .billing_statement_line_items()
+            .replace_all(&BillingStatementId::new("bstm_123"), items)
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].id.as_str(), "bstm_li_3");
+
+        let requests = server.received_requests().await.unwrap();
+        let sequence: Vec<_> = requests
+            .iter()
+            .map(|r| format!("{} {}", r.method, r.url.path()))
+            .collect();
+        assert_eq!(
+            sequence,
+            [
+                "GET /billing_statements/bstm_123",
+                "DELETE /billing_statement_line_items/bstm_li_1",
+                "DELETE /billing_statement_line_items/bstm_li_2",
+                "POST /billing_statement_line_items",
+            ]
+        );
+        let body = String::from_utf8_lossy(&requests[3].body);
+        assert!(body.contains("billing_statement_id=bstm_123"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_replace_all_rejects_before_mutating() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let statement = |id: &str, status: &str| {
+            serde_json::json!({
+                "id": id,
+                "amount": 6000,
+                "currency": "PHP",
+                "customer_id": "cus_123",
+                "livemode": false,
+                "status": status,
+                "payment_settings": { "payment_methods": ["card"] },
+                "created_at": 1_621_000_000,
+                "updated_at": 1_621_000_000,
+            })
+        };
+
+        let server = MockServer::start().await;
+        let mut open = statement("bstm_open", "open");
+        open["line_items"] = serde_json::json!([]);
+        Mock::given(method("GET"))
+            .and(path("/billing_statements/bstm_open"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(open))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/billing_statements/bstm_bare"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(statement("bstm_bare", "draft")))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let line_items = client.billing_statement_line_items();
+        let new_items = |id: &str| {
+            vec![CreateBillingStatementLineItem::new(
+                BillingStatementId::new(id),
+                "New",
+                1500,
+                2,
+            )]
+        };
+
+        let result = line_items
+            .replace_all(
+                &BillingStatementId::new("bstm_open"),
+                new_items("bstm_open"),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("draft")));
+
+        let result = line_items
+            .replace_all(
+                &BillingStatementId::new("bstm_bare"),
+                new_items("bstm_bare"),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("line items")));
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().all(|r| r.method.as_str() == "GET"));
+    }
+
     #[test]
     fn test_update_billing_statement_line_item_serialization() {
         let params = UpdateBillingStatementLineItem::new()
@@ -184,5 +378,9 @@ mod tests {
             serialized,
             r#"{"description":"Example description","unit_price":500,"quantity":1}"#
         );
+
+        let params = UpdateBillingStatementLineItem::new().quantity(2);
+        let serialized = serde_json::to_string(&params).unwrap();
+        assert_eq!(serialized, r#"{"quantity":2}"#);
     }
 }
@@ -5,12 +5,12 @@
 use crate::resources::billing_statement_line_items::BillingStatementLineItem;
 use crate::resources::payment_intents::OptionalPaymentIntent;
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
-    resources::customers::OptionalCustomer,
+    resources::{customers::OptionalCustomer, paths},
     types::{
-        BillingStatementId, Currency, CustomerId, List, ListParams, Metadata, PaymentMethod,
-        Timestamp,
+        BillingStatementId, Currency, CustomerId, List, ListParams, Metadata, Patch, PaymentMethod,
+        Snapshot, Timestamp,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -34,8 +34,11 @@ impl BillingStatements {
     /// Endpoint: `POST /billing_statements`
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/create)
-    pub async fn create(&self, params: CreateBillingStatement) -> Result<BillingStatement> {
-        self.http.post("/billing_statements", &params).await
+    pub async fn create(&self, mut params: CreateBillingStatement) -> Result<BillingStatement> {
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post(paths::collection::<BillingStatementId>(), &params)
+            .await
     }
 
     /// Retrieves a billing statement resource.
@@ -44,9 +47,7 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/retrieve)
     pub async fn retrieve(&self, id: &BillingStatementId) -> Result<BillingStatement> {
-        self.http
-            .get(&format!("/billing_statements/{}", id.as_str()))
-            .await
+        self.http.get(&paths::resource(id)).await
     }
 
     /// Updates a billing statement resource.
@@ -59,9 +60,7 @@ impl BillingStatements {
         id: &BillingStatementId,
         params: UpdateBillingStatement,
     ) -> Result<BillingStatement> {
-        self.http
-            .put(&format!("/billing_statements/{}", id.as_str()), &params)
-            .await
+        self.http.put(&paths::resource(id), &params).await
     }
 
     /// Deletes a billing statement resource.
@@ -70,9 +69,7 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/delete)
     pub async fn delete(&self, id: &BillingStatementId) -> Result<()> {
-        self.http
-            .delete(&format!("/billing_statements/{}", id.as_str()))
-            .await
+        self.http.delete(&paths::resource(id)).await
     }
 
     /// List billing statement resources.
@@ -81,8 +78,11 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/list)
     pub async fn list(&self, params: Option<ListParams>) -> Result<List<BillingStatement>> {
+        if let Some(params) = &params {
+            params.validate()?;
+        }
         self.http
-            .get_with_params("/billing_statements", &params)
+            .get_with_params(paths::collection::<BillingStatementId>(), &params)
             .await
     }
 
@@ -92,12 +92,46 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/finalize)
     pub async fn finalize(&self, id: &BillingStatementId) -> Result<BillingStatement> {
-        self.http
-            .post(
-                &format!("/billing_statements/{}/finalize", id.as_str()),
-                &(),
-            )
-            .await
+        self.http.post(&paths::action(id, "finalize"), &()).await
+    }
+
+    /// Finalizes a billing statement after checking its line items add up to a valid amount.
+    ///
+    /// Line items can be changed through [`BillingStatementLineItems`] after the statement was
+    /// fetched, so the statement is re-retrieved first. Returns [`Error::InvalidRequest`] without
+    /// finalizing if the line-item total is below [`BillingStatement::MINIMUM_AMOUNT`], does not
+    /// match the statement's `amount`, or cannot be checked because PayRex did not return the
+    /// line items.
+    ///
+    /// [`BillingStatementLineItems`]: crate::resources::BillingStatementLineItems
+    pub async fn finalize_checked(&self, id: &BillingStatementId) -> Result<BillingStatement> {
+        let statement = self.retrieve(id).await?;
+        let total = statement.line_items_total().ok_or_else(|| {
+            Error::invalid_request(format!(
+                "billing statement {} was returned without its line items, so its total cannot \
+                 be checked",
+                id.as_str()
+            ))
+        })?;
+
+        let currency = statement.currency;
+        if total < currency.minimum_amount() {
+            return Err(Error::invalid_request(format!(
+                "billing statement {} totals {}, below the minimum of {}",
+                id.as_str(),
+                currency.format_amount(total),
+                currency.format_amount(currency.minimum_amount())
+            )));
+        }
+        if total != statement.amount {
+            return Err(Error::invalid_request(format!(
+                "billing statement {} amount {} does not match its line-item total {total}",
+                id.as_str(),
+                statement.amount
+            )));
+        }
+
+        self.finalize(id).await
     }
 
     /// Send a billing statement via e-mail.
@@ -106,9 +140,31 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/send)
     pub async fn send(&self, id: &BillingStatementId) -> Result<BillingStatement> {
-        self.http
-            .post(&format!("/billing_statements/{}/send", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "send"), &()).await
+    }
+
+    /// Send a billing statement via e-mail with custom delivery options, e.g. to an alternate
+    /// billing contact without changing the customer record.
+    ///
+    /// Endpoint: `POST /billing_statements/:id/send`
+    ///
+    /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/send)
+    pub async fn send_with(
+        &self,
+        id: &BillingStatementId,
+        params: SendBillingStatement,
+    ) -> Result<BillingStatement> {
+        self.http.post(&paths::action(id, "send"), &params).await
+    }
+
+    /// Finalizes a billing statement and then sends it via e-mail, returning the sent statement.
+    ///
+    /// The two steps are separate API calls. If finalizing succeeds but sending fails, the
+    /// statement stays finalized and the error from `send` is returned; retry with
+    /// [`BillingStatements::send`] rather than calling this again.
+    pub async fn finalize_and_send(&self, id: &BillingStatementId) -> Result<BillingStatement> {
+        self.finalize(id).await?;
+        self.send(id).await
     }
 
     /// Voids a billing statement resource.
@@ -117,17 +173,12 @@ impl BillingStatements {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/billing_statements/void)
     pub async fn void(&self, id: &BillingStatementId) -> Result<BillingStatement> {
-        self.http
-            .post(&format!("/billing_statements/{}/void", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "void"), &()).await
     }
 
     pub async fn mark_uncollectible(&self, id: &BillingStatementId) -> Result<BillingStatement> {
         self.http
-            .post(
-                &format!("/billing_statements/{}/mark_uncollectible", id.as_str()),
-                &(),
-            )
+            .post(&paths::action(id, "mark_uncollectible"), &())
             .await
     }
 }
@@ -203,6 +254,7 @@ pub struct BillingStatement {
 
     /// The value is `true` if the resource's mode is live, and the value is `false` if the resource is
     /// in test mode.
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
 
     /// Set of key-value pairs attached to the billing statement. This is useful for storing
@@ -226,18 +278,61 @@ pub struct BillingStatement {
     pub customer: Option<OptionalCustomer>,
 
     /// The time the resource was created and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `created`, the field name used by older payloads.
+    #[serde(alias = "created")]
     pub created_at: Timestamp,
 
     /// The time the resource was updated and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `updated`, the field name used by older payloads.
+    #[serde(alias = "updated")]
     pub updated_at: Timestamp,
 }
 
+impl BillingStatement {
+    /// The smallest amount a billing statement can be finalized with, ₱ 20 in cents.
+    pub const MINIMUM_AMOUNT: i64 = Currency::PHP.minimum_amount();
+
+    /// Returns the sum of `quantity * unit_price` over the line items, or `None` if the line
+    /// items were not included in the response.
+    ///
+    /// Saturates at `i64::MAX` rather than overflowing.
+    #[must_use]
+    pub fn line_items_total(&self) -> Option<i64> {
+        self.line_items.as_ref().map(|items| {
+            items.iter().fold(0i64, |total, item| {
+                let amount = item.unit_price.saturating_mul(item.quantity);
+                total.saturating_add(i64::try_from(amount).unwrap_or(i64::MAX))
+            })
+        })
+    }
+}
+
+impl Snapshot for BillingStatement {
+    type Status = BillingStatementStatus;
+
+    fn updated_at(&self) -> Timestamp {
+        self.updated_at
+    }
+
+    fn status(&self) -> Self::Status {
+        self.status
+    }
+
+    fn can_transition(from: Self::Status, to: Self::Status) -> bool {
+        from.can_transition_to(to)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaymentSettings {
     pub payment_methods: Vec<PaymentMethod>,
 }
 
 /// The latest status of the [`BillingStatement`].
+///
+/// [Reference](https://docs.payrexhq.com/docs/api/billing_statements)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BillingStatementStatus {
@@ -250,11 +345,37 @@ pub enum BillingStatementStatus {
     /// The latest status is paid.
     Paid,
 
-    /// The latest status is uncollectible.
+    /// The billing statement was voided and can no longer be paid.
     Void,
 
-    /// The latest status is uncollectible.
+    /// The billing statement was marked as uncollectible.
     Uncollectible,
+
+    /// A status this version of the SDK does not know about.
+    #[serde(other)]
+    Unknown,
+}
+
+impl BillingStatementStatus {
+    /// Returns `true` if a billing statement in this status can move to `next`.
+    ///
+    /// A draft is finalized into an open statement, which is then paid, voided, or marked
+    /// uncollectible. An uncollectible statement can still be paid or voided, while
+    /// [`BillingStatementStatus::Paid`] and [`BillingStatementStatus::Void`] are final. Staying
+    /// in the same status is always allowed, and [`BillingStatementStatus::Unknown`] is not
+    /// restricted.
+    #[must_use]
+    pub const fn can_transition_to(self, next: Self) -> bool {
+        match self {
+            Self::Draft | Self::Unknown => true,
+            Self::Open => !matches!(next, Self::Draft),
+            Self::Uncollectible => {
+                matches!(next, Self::Uncollectible | Self::Paid | Self::Void)
+            }
+            Self::Paid => matches!(next, Self::Paid),
+            Self::Void => matches!(next, Self::Void),
+        }
+    }
 }
 
 /// Query parameters when creating a billing statement.
@@ -323,7 +444,54 @@ pub struct UpdateBillingStatement {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
 
-    pub due_at: Option<Timestamp>,
+    /// When the billing statement is due. Can be cleared with [`Patch::Clear`].
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub due_at: Patch<Timestamp>,
+}
+
+/// Query parameters when sending a billing statement.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendBillingStatement {
+    /// The e-mail address to send the billing statement to instead of the customer's e-mail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// A custom message included in the e-mail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Additional e-mail addresses that receive a copy of the billing statement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<Vec<String>>,
+}
+
+impl SendBillingStatement {
+    /// Creates a new [`SendBillingStatement`] instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the recipient e-mail address.
+    #[must_use]
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Sets the custom message.
+    #[must_use]
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Adds an e-mail address to copy.
+    #[must_use]
+    pub fn cc(mut self, email: impl Into<String>) -> Self {
+        self.cc.get_or_insert_with(Vec::new).push(email.into());
+        self
+    }
 }
 
 impl CreateBillingStatement {
@@ -354,8 +522,8 @@ impl CreateBillingStatement {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 }
@@ -386,8 +554,22 @@ impl UpdateBillingStatement {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
+        self
+    }
+
+    /// Sets when the billing statement is due.
+    #[must_use]
+    pub const fn due_at(mut self, due_at: Timestamp) -> Self {
+        self.due_at = Patch::Set(due_at);
+        self
+    }
+
+    /// Removes the due date of the billing statement.
+    #[must_use]
+    pub fn clear_due_at(mut self) -> Self {
+        self.due_at = Patch::Clear;
         self
     }
 }
@@ -396,6 +578,7 @@ impl UpdateBillingStatement {
 mod tests {
     use super::*;
     use super::{BillingStatementStatus, PaymentSettings};
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::BillingStatementLineItemId;
     use crate::types::{
         BillingStatementId, Currency, CustomerId, Metadata, PaymentMethod, Timestamp,
@@ -426,6 +609,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_billing_statement_status_round_trip() {
+        use BillingStatementStatus::*;
+
+        for (status, json) in [
+            (Draft, "draft"),
+            (Open, "open"),
+            (Paid, "paid"),
+            (Void, "void"),
+            (Uncollectible, "uncollectible"),
+        ] {
+            assert_eq!(serde_json::to_value(status).unwrap(), json);
+            assert_eq!(
+                serde_json::from_value::<BillingStatementStatus>(json.into()).unwrap(),
+                status
+            );
+        }
+        assert_ne!(
+            serde_json::from_value::<BillingStatementStatus>("void".into()).unwrap(),
+            Uncollectible
+        );
+        assert_eq!(
+            serde_json::from_value::<BillingStatementStatus>("past_due".into()).unwrap(),
+            Unknown
+        );
+    }
+
+    #[test]
+    fn test_billing_statement_status_transitions() {
+        use BillingStatementStatus::*;
+
+        assert!(Draft.can_transition_to(Open));
+        assert!(Open.can_transition_to(Paid));
+        assert!(Open.can_transition_to(Uncollectible));
+        assert!(!Open.can_transition_to(Draft));
+        assert!(Uncollectible.can_transition_to(Paid));
+        assert!(!Uncollectible.can_transition_to(Open));
+        for next in [Draft, Open, Void, Uncollectible] {
+            assert!(!Paid.can_transition_to(next));
+        }
+        assert!(!Void.can_transition_to(Paid));
+        assert!(Void.can_transition_to(Void));
+    }
+
+    #[test]
+    fn test_billing_statement_is_stale_relative_to() {
+        let snapshot = |status: &str, updated_at: i64| -> BillingStatement {
+            let mut statement = statement_with_line_items(5000, &[]);
+            statement["status"] = status.into();
+            statement["updated_at"] = updated_at.into();
+            serde_json::from_value(statement).unwrap()
+        };
+
+        let paid = snapshot("paid", 1_620_002_000);
+        let late_event = snapshot("open", 1_620_001_000);
+        assert!(late_event.is_stale_relative_to(&paid));
+        assert!(!paid.is_stale_relative_to(&late_event));
+
+        let open = snapshot("open", 1_620_002_000);
+        assert!(open.is_stale_relative_to(&paid));
+        assert!(!paid.is_stale_relative_to(&open));
+    }
+
     #[test]
     fn test_payment_settings_serialization() {
         let settings = PaymentSettings {
@@ -483,6 +729,253 @@ mod tests {
         assert_eq!(json["payment_settings"]["payment_methods"][0], "maya");
         assert_eq!(json["description"], "upd");
         assert_eq!(json["metadata"]["x"], "y");
+        assert!(json.get("due_at").is_none());
+    }
+
+    #[test]
+    fn test_update_billing_statement_omits_unset_fields() {
+        let json = serde_json::to_value(UpdateBillingStatement::new()).unwrap();
+        assert_eq!(json, serde_json::json!({}));
+
+        let params = UpdateBillingStatement::new().due_at(Timestamp::from_unix(1_700_000_000));
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json, serde_json::json!({ "due_at": 1_700_000_000 }));
+
+        let json = serde_json::to_value(UpdateBillingStatement::new().clear_due_at()).unwrap();
+        assert_eq!(json, serde_json::json!({ "due_at": null }));
+    }
+
+    #[test]
+    fn test_send_billing_statement_serialization() {
+        let params = SendBillingStatement::new()
+            .email("billing@example.com")
+            .message("Thanks for your business")
+            .cc("finance@example.com")
+            .cc("owner@example.com");
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["email"], "billing@example.com");
+        assert_eq!(json["message"], "Thanks for your business");
+        assert_eq!(json["cc"][0], "finance@example.com");
+        assert_eq!(json["cc"][1], "owner@example.com");
+
+        let empty = serde_json::to_string(&SendBillingStatement::new()).unwrap();
+        assert_eq!(empty, "{}");
+    }
+
+    #[tokio::test]
+    async fn test_send_without_options_sends_empty_body() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing_statements/bstm_123/send"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let _ = client
+            .billing_statements()
+            .send(&BillingStatementId::new("bstm_123"))
+            .await;
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_finalize_and_send_hits_both_endpoints_in_order() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let statement = |status: &str| {
+            serde_json::json!({
+                "id": "bstm_123",
+                "amount": 2000,
+                "currency": "PHP",
+                "customer_id": "cus_999",
+                "livemode": false,
+                "status": status,
+                "payment_settings": { "payment_methods": ["card"] },
+                "created_at": 1_620_000_000,
+                "updated_at": 1_620_001_000,
+            })
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing_statements/bstm_123/finalize"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(statement("open")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut sent = statement("open");
+        sent["billing_statement_number"] = "BS100".into();
+        Mock::given(method("POST"))
+            .and(path("/billing_statements/bstm_123/send"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(sent))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let statement = client
+            .billing_statements()
+            .finalize_and_send(&BillingStatementId::new("bstm_123"))
+            .await
+            .unwrap();
+
+        assert_eq!(statement.status, BillingStatementStatus::Open);
+        assert_eq!(statement.billing_statement_number.as_deref(), Some("BS100"));
+
+        let requests = server.received_requests().await.unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.url.path()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/billing_statements/bstm_123/finalize",
+                "/billing_statements/bstm_123/send"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finalize_and_send_stops_when_finalize_fails() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing_statements/bstm_123/finalize"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let result = client
+            .billing_statements()
+            .finalize_and_send(&BillingStatementId::new("bstm_123"))
+            .await;
+
+        assert_eq!(result.unwrap_err().status_code(), Some(400));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    fn statement_with_line_items(amount: i64, line_items: &[(u64, u64)]) -> serde_json::Value {
+        let line_items: Vec<_> = line_items
+            .iter()
+            .enumerate()
+            .map(|(i, (unit_price, quantity))| {
+                serde_json::json!({
+                    "id": format!("bstm_li_{i}"),
+                    "unit_price": unit_price,
+                    "quantity": quantity,
+                    "billing_statement_id": "bstm_123",
+                    "livemode": false,
+                    "created_at": 1_620_000_000,
+                    "updated_at": 1_620_000_000,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": "bstm_123",
+            "amount": amount,
+            "currency": "PHP",
+            "customer_id": "cus_999",
+            "livemode": false,
+            "status": "draft",
+            "line_items": line_items,
+            "payment_settings": { "payment_methods": ["card"] },
+            "created_at": 1_620_000_000,
+            "updated_at": 1_620_001_000,
+        })
+    }
+
+    async fn finalize_checked_against(
+        statement: serde_json::Value,
+    ) -> (Result<BillingStatement>, usize) {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/billing_statements/bstm_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&statement))
+            .mount(&server)
+            .await;
+        let mut finalized = statement;
+        finalized["status"] = "open".into();
+        Mock::given(method("POST"))
+            .and(path("/billing_statements/bstm_123/finalize"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(finalized))
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let result = client
+            .billing_statements()
+            .finalize_checked(&BillingStatementId::new("bstm_123"))
+            .await;
+        (result, server.received_requests().await.unwrap().len())
+    }
+
+    #[tokio::test]
+    async fn test_finalize_checked_finalizes_valid_statement() {
+        let (result, requests) =
+            finalize_checked_against(statement_with_line_items(5000, &[(1500, 2), (2000, 1)]))
+                .await;
+
+        assert_eq!(result.unwrap().status, BillingStatementStatus::Open);
+        assert_eq!(requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_checked_rejects_below_minimum() {
+        let (result, requests) = finalize_checked_against(statement_with_line_items(0, &[])).await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("minimum of ₱20.00")));
+        assert_eq!(requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_checked_rejects_stale_amount() {
+        let (result, requests) =
+            finalize_checked_against(statement_with_line_items(5000, &[(1500, 2)])).await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("does not match")));
+        assert_eq!(requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_checked_rejects_missing_line_items() {
+        let mut statement = statement_with_line_items(5000, &[]);
+        statement.as_object_mut().unwrap().remove("line_items");
+        let (result, requests) = finalize_checked_against(statement).await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("line items")));
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn test_billing_statement_accepts_legacy_field_names() {
+        let statement: BillingStatement = serde_json::from_value(serde_json::json!({
+            "id": "bstm_123",
+            "amount": 2000,
+            "currency": "PHP",
+            "customer_id": "cus_999",
+            "livemode": false,
+            "status": "draft",
+            "payment_settings": { "payment_methods": ["card"] },
+            "created": 1_620_000_000,
+            "updated": 1_620_001_000,
+        }))
+        .unwrap();
+
+        assert_eq!(statement.created_at, Timestamp::from_unix(1_620_000_000));
+        assert_eq!(statement.updated_at, Timestamp::from_unix(1_620_001_000));
     }
 
     #[test]
@@ -529,6 +1022,8 @@ mod tests {
             updated_at: Timestamp::from_unix(1_620_001_000),
         };
 
+        assert_round_trip(&stmt);
+
         let json = serde_json::to_value(&stmt).unwrap();
         assert_eq!(json["id"], "bstm_123");
         assert_eq!(json["amount"], 2000);
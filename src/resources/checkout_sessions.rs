@@ -3,17 +3,20 @@
 //! Checkout Sessions create a hosted payment page for collecting payment.
 
 use crate::{
-    Result,
+    Client, Error, Result,
     http::HttpClient,
-    resources::payment_intents::PaymentIntent,
+    resources::{customers::Customer, paths, payment_intents::PaymentIntent},
     types::{
-        CheckoutSessionId, CheckoutSessionLineItemId, Currency, Metadata, PaymentMethod,
-        PaymentMethodOptions, Timestamp,
+        CheckoutSessionId, CheckoutSessionLineItemId, Currency, CustomerId, Expandable, Metadata,
+        PaymentIntentId, PaymentMethod, PaymentMethodOptions, Secret, Snapshot, Timestamp,
     },
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// The longest a checkout session can stay open, measured from the time it is created.
+pub const MAX_EXPIRY_WINDOW_SECS: i64 = 24 * 60 * 60;
+
 #[derive(Clone)]
 pub struct CheckoutSessions {
     http: Arc<HttpClient>,
@@ -25,20 +28,33 @@ impl CheckoutSessions {
         Self { http }
     }
 
-    pub async fn create(&self, params: CreateCheckoutSession) -> Result<CheckoutSession> {
-        self.http.post("/checkout_sessions", &params).await
+    pub async fn create(&self, mut params: CreateCheckoutSession) -> Result<CheckoutSession> {
+        params.validate()?;
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post(paths::collection::<CheckoutSessionId>(), &params)
+            .await
     }
 
     pub async fn retrieve(&self, id: &CheckoutSessionId) -> Result<CheckoutSession> {
+        self.http.get(&paths::resource(id)).await
+    }
+
+    /// Retrieves a checkout session with its payment intent embedded in
+    /// [`CheckoutSession::payment_intent`].
+    ///
+    /// Endpoint: `GET /checkout_sessions/:id?expand[]=payment_intent`
+    pub async fn retrieve_with_payment_intent(
+        &self,
+        id: &CheckoutSessionId,
+    ) -> Result<CheckoutSession> {
         self.http
-            .get(&format!("/checkout_sessions/{}", id.as_str()))
+            .get_expanded(&paths::resource(id), &["payment_intent"])
             .await
     }
 
     pub async fn expire(&self, id: &CheckoutSessionId) -> Result<CheckoutSession> {
-        self.http
-            .post(&format!("/checkout_sessions/{}/expire", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "expire"), &()).await
     }
 }
 
@@ -49,13 +65,21 @@ pub struct CheckoutSession {
     pub amount: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer_reference_id: Option<String>,
+    /// The customer associated with the checkout session, either as an ID or as an expanded
+    /// [`Customer`] object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Expandable<CustomerId, Customer>>,
+    /// Whether the session creates a [`Customer`] from the collected billing details.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_creation: Option<CustomerCreation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub billing_details_collection: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub client_secret: Option<String>,
+    pub client_secret: Option<Secret<String>>,
     pub status: CheckoutSessionStatus,
     pub currency: Currency,
     pub line_items: Vec<CheckoutSessionLineItem>,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -82,6 +106,63 @@ pub struct CheckoutSession {
     pub updated_at: Timestamp,
 }
 
+impl CheckoutSession {
+    /// Returns the ID of the customer linked to the session, whether it was passed in or
+    /// created by [`CustomerCreation`], and whether `customer` is expanded or not.
+    #[must_use]
+    pub fn customer_id(&self) -> Option<&CustomerId> {
+        self.customer.as_ref().map(|customer| match customer {
+            Expandable::Id(id) => id,
+            Expandable::Object(customer) => &customer.id,
+        })
+    }
+
+    /// Returns the ID of the payment intent embedded in the checkout session, if any.
+    #[must_use]
+    pub fn payment_intent_id(&self) -> Option<&PaymentIntentId> {
+        self.payment_intent.as_ref().map(|intent| &intent.id)
+    }
+
+    /// Returns the session's payment intent, retrieving the session again with the intent
+    /// expanded when it is not embedded in this copy.
+    ///
+    /// Returns [`Error::NotFound`] if PayRex has not attached a payment intent to the session,
+    /// e.g. because the customer has not completed checkout yet.
+    pub async fn ensure_payment_intent(&self, client: &Client) -> Result<PaymentIntent> {
+        if let Some(intent) = &self.payment_intent {
+            return Ok(intent.clone());
+        }
+
+        client
+            .checkout_sessions()
+            .retrieve_with_payment_intent(&self.id)
+            .await?
+            .payment_intent
+            .ok_or_else(|| {
+                Error::not_found(format!(
+                    "checkout session {} has no payment intent",
+                    self.id.as_str()
+                ))
+            })
+    }
+}
+
+impl Snapshot for CheckoutSession {
+    type Status = CheckoutSessionStatus;
+
+    fn updated_at(&self) -> Timestamp {
+        self.updated_at
+    }
+
+    fn status(&self) -> Self::Status {
+        self.status
+    }
+
+    fn can_transition(from: Self::Status, to: Self::Status) -> bool {
+        from.can_transition_to(to)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CheckoutSessionStatus {
@@ -90,23 +171,68 @@ pub enum CheckoutSessionStatus {
     Expired,
 }
 
+impl CheckoutSessionStatus {
+    /// Returns `true` if a session in this status can move to `next`.
+    ///
+    /// An active session either completes or expires, and both of those are final. Staying in
+    /// the same status is always allowed, so a redelivered webhook is not flagged as out of
+    /// order.
+    #[must_use]
+    pub const fn can_transition_to(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Active, _) | (Self::Completed, Self::Completed) | (Self::Expired, Self::Expired)
+        )
+    }
+}
+
+/// Controls whether a checkout session creates a [`Customer`] from the billing details it
+/// collects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomerCreation {
+    /// Always create a customer.
+    Always,
+
+    /// Only create a customer when one is required, e.g. to save the payment method.
+    IfRequired,
+}
+
+/// A line item of a checkout session.
+///
+/// The same type is used when creating a session and when reading one back. Fields assigned by
+/// PayRex are `None` on create and populated in responses.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CheckoutSessionLineItem {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Unique identifier for the line item, assigned by PayRex. The prefix is `cs_li_`.
+    ///
+    /// Always `None` when creating a session and always set on a session returned by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<CheckoutSessionLineItemId>,
     pub name: String,
     pub amount: u64,
     pub quantity: u64,
+    /// The currency of `amount`, assigned by PayRex. Line items are always in the session's
+    /// currency, so this is `None` on create.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Parameters when creating a checkout session.
+///
+/// The [`Default`] value leaves the required fields empty and is rejected by
+/// [`CreateCheckoutSession::validate`] until they are filled in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateCheckoutSession {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer_reference_id: Option<String>,
+    /// Whether to create a [`Customer`] from the billing details collected at checkout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_creation: Option<CustomerCreation>,
     pub currency: Currency,
     pub line_items: Vec<CheckoutSessionLineItem>,
     pub success_url: String,
@@ -137,6 +263,7 @@ impl CreateCheckoutSession {
     ) -> Self {
         Self {
             customer_reference_id: None,
+            customer_creation: None,
             currency,
             line_items,
             success_url: success_url.into(),
@@ -156,11 +283,42 @@ impl CreateCheckoutSession {
         self
     }
 
+    /// Sets whether the session creates a [`Customer`] from the collected billing details.
+    pub const fn customer_creation(mut self, customer_creation: CustomerCreation) -> Self {
+        self.customer_creation = Some(customer_creation);
+        self
+    }
+
     pub fn expires_at(mut self, timestamp: Timestamp) -> Self {
         self.expires_at = Some(timestamp);
         self
     }
 
+    /// Sets `expires_at` after checking that it is in the future and within
+    /// [`MAX_EXPIRY_WINDOW_SECS`] from now.
+    ///
+    /// Passing a Unix timestamp in milliseconds by mistake lands far outside the window and is
+    /// rejected here instead of by the API.
+    pub fn try_expires_at(mut self, timestamp: Timestamp) -> Result<Self> {
+        let now = Timestamp::now().as_unix();
+        let expires_at = timestamp.as_unix();
+
+        if expires_at <= now {
+            return Err(Error::invalid_request(format!(
+                "expires_at must be in the future, got {timestamp}"
+            )));
+        }
+
+        if expires_at - now > MAX_EXPIRY_WINDOW_SECS {
+            return Err(Error::invalid_request(format!(
+                "expires_at must be within {MAX_EXPIRY_WINDOW_SECS} seconds from now, got {expires_at}"
+            )));
+        }
+
+        self.expires_at = Some(timestamp);
+        Ok(self)
+    }
+
     pub fn payment_method_options(mut self, options: PaymentMethodOptions) -> Self {
         self.payment_method_options = Some(options);
         self
@@ -181,10 +339,52 @@ impl CreateCheckoutSession {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
+
+    /// Checks that the required fields are set, and that `success_url` and `cancel_url` are
+    /// valid http(s) URLs that differ from each other, so post-checkout routing can tell a
+    /// completed session from a cancelled one.
+    ///
+    /// Called by [`CheckoutSessions::create`] before the request is sent.
+    pub fn validate(&self) -> Result<()> {
+        if self.line_items.is_empty() {
+            return Err(Error::invalid_request(
+                "line_items must contain at least one line item",
+            ));
+        }
+        if self.payment_methods.is_empty() {
+            return Err(Error::invalid_request(
+                "payment_methods must contain at least one payment method",
+            ));
+        }
+
+        let success_url = parse_http_url("success_url", &self.success_url)?;
+        let cancel_url = parse_http_url("cancel_url", &self.cancel_url)?;
+
+        if success_url == cancel_url {
+            return Err(Error::invalid_request(
+                "success_url and cancel_url must be different",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_http_url(field: &str, value: &str) -> Result<url::Url> {
+    let url = url::Url::parse(value)
+        .map_err(|e| Error::invalid_request(format!("{field} is not a valid URL: {e}")))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::invalid_request(format!(
+            "{field} must be an http or https URL, got {value}"
+        )));
+    }
+
+    Ok(url)
 }
 
 impl CheckoutSessionLineItem {
@@ -195,6 +395,7 @@ impl CheckoutSessionLineItem {
             name: name.into(),
             amount,
             quantity,
+            currency: None,
             description: None,
             image: None,
         }
@@ -214,12 +415,57 @@ impl CheckoutSessionLineItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::{
         CheckoutSessionId, CheckoutSessionLineItemId, Currency, Metadata, PaymentMethod,
         PaymentMethodOptions, Timestamp,
     };
     use serde_json;
 
+    #[test]
+    fn test_checkout_session_status_transitions() {
+        use CheckoutSessionStatus::*;
+
+        assert!(Active.can_transition_to(Active));
+        assert!(Active.can_transition_to(Completed));
+        assert!(Active.can_transition_to(Expired));
+
+        assert!(Completed.can_transition_to(Completed));
+        assert!(!Completed.can_transition_to(Active));
+        assert!(!Completed.can_transition_to(Expired));
+
+        assert!(Expired.can_transition_to(Expired));
+        assert!(!Expired.can_transition_to(Active));
+        assert!(!Expired.can_transition_to(Completed));
+    }
+
+    #[test]
+    fn test_checkout_session_is_stale_relative_to() {
+        let snapshot = |status: &str, updated_at: i64| -> CheckoutSession {
+            serde_json::from_value(serde_json::json!({
+                "id": "cs_123",
+                "status": status,
+                "currency": "PHP",
+                "line_items": [],
+                "livemode": false,
+                "url": "https://checkout.payrexhq.com/c/cs_123",
+                "created_at": 1_700_000_000,
+                "updated_at": updated_at,
+            }))
+            .unwrap()
+        };
+
+        let completed = snapshot("completed", 1_700_000_200);
+        let late_event = snapshot("active", 1_700_000_100);
+        assert!(late_event.is_stale_relative_to(&completed));
+        assert!(!completed.is_stale_relative_to(&late_event));
+
+        let active = snapshot("active", 1_700_000_200);
+        assert!(active.is_stale_relative_to(&completed));
+        assert!(!completed.is_stale_relative_to(&active));
+        assert!(!active.is_stale_relative_to(&active.clone()));
+    }
+
     #[test]
     fn test_checkout_session_status_serialization() {
         assert_eq!(
@@ -268,6 +514,38 @@ mod tests {
         assert_eq!(json["id"], "cs_li_123");
     }
 
+    #[test]
+    fn test_checkout_session_line_item_ids_deserialize() {
+        let session: CheckoutSession = serde_json::from_value(serde_json::json!({
+            "id": "cs_123",
+            "status": "completed",
+            "currency": "PHP",
+            "line_items": [
+                { "id": "cs_li_1", "name": "Shirt", "amount": 50000, "quantity": 2, "currency": "PHP" },
+                { "id": "cs_li_2", "name": "Socks", "amount": 15000, "quantity": 1 },
+            ],
+            "livemode": false,
+            "url": "https://checkout.payrexhq.com/c/cs_123",
+            "created_at": 1_700_000_000,
+            "updated_at": 1_700_000_100,
+        }))
+        .unwrap();
+
+        let ids: Vec<_> = session
+            .line_items
+            .iter()
+            .map(|item| item.id.as_ref().unwrap().as_str())
+            .collect();
+        assert_eq!(ids, ["cs_li_1", "cs_li_2"]);
+        assert_eq!(session.line_items[0].currency, Some(Currency::PHP));
+        assert_eq!(session.line_items[1].currency, None);
+
+        let create = CheckoutSessionLineItem::new("Shirt", 50000, 2);
+        let json = serde_json::to_value(&create).unwrap();
+        assert!(json.get("id").is_none());
+        assert!(json.get("currency").is_none());
+    }
+
     #[test]
     fn test_create_checkout_session_builder() {
         let line_item = CheckoutSessionLineItem::new("Item A", 1000, 1);
@@ -331,6 +609,252 @@ mod tests {
         assert_eq!(json["metadata"]["foo"], "bar");
     }
 
+    #[test]
+    fn test_create_checkout_session_customer_creation() {
+        assert_eq!(
+            serde_json::to_value(CustomerCreation::Always).unwrap(),
+            "always"
+        );
+        assert_eq!(
+            serde_json::to_value(CustomerCreation::IfRequired).unwrap(),
+            "if_required"
+        );
+
+        let json = serde_json::to_value(create_params()).unwrap();
+        assert!(json.get("customer_creation").is_none());
+
+        let params = create_params().customer_creation(CustomerCreation::IfRequired);
+        assert_eq!(params.customer_creation, Some(CustomerCreation::IfRequired));
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["customer_creation"], "if_required");
+    }
+
+    #[test]
+    fn test_checkout_session_created_customer() {
+        let mut json = checkout_session_json(serde_json::json!("cus_123"));
+        json["customer_creation"] = "always".into();
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+
+        assert_eq!(session.customer_creation, Some(CustomerCreation::Always));
+        assert_eq!(session.customer_id().unwrap().as_str(), "cus_123");
+
+        let json = checkout_session_json(serde_json::json!({
+            "id": "cus_456",
+            "livemode": false,
+            "created_at": 1_609_459_200,
+            "updated_at": 1_609_459_300,
+        }));
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+        assert_eq!(session.customer_id().unwrap().as_str(), "cus_456");
+
+        let session: CheckoutSession =
+            serde_json::from_value(checkout_session_json(serde_json::Value::Null)).unwrap();
+        assert_eq!(session.customer_id(), None);
+    }
+
+    fn create_params() -> CreateCheckoutSession {
+        CreateCheckoutSession::new(
+            Currency::PHP,
+            vec![CheckoutSessionLineItem::new("Item A", 1000, 1)],
+            "https://success",
+            "https://cancel",
+            vec![PaymentMethod::Card],
+        )
+    }
+
+    #[test]
+    fn test_validate_rejects_default() {
+        let result = CreateCheckoutSession::default().validate();
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("line_items")));
+
+        let params = CreateCheckoutSession {
+            payment_methods: Vec::new(),
+            ..create_params()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(Error::InvalidRequest(m)) if m.contains("payment_methods")
+        ));
+    }
+
+    #[test]
+    fn test_validate_distinct_urls() {
+        assert!(create_params().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_identical_urls() {
+        let mut params = create_params();
+        params.cancel_url = params.success_url.clone();
+        assert!(matches!(params.validate(), Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_validate_invalid_urls() {
+        let mut params = create_params();
+        params.success_url = "not a url".to_string();
+        assert!(matches!(params.validate(), Err(Error::InvalidRequest(_))));
+
+        let mut params = create_params();
+        params.cancel_url = "ftp://example.com/cancel".to_string();
+        assert!(matches!(params.validate(), Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_try_expires_at_rejects_past() {
+        let past = Timestamp::from_unix(Timestamp::now().as_unix() - 60);
+        let result = create_params().try_expires_at(past);
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_try_expires_at_rejects_too_far_future() {
+        let now = Timestamp::now().as_unix();
+        let too_far = Timestamp::from_unix(now + MAX_EXPIRY_WINDOW_SECS + 60);
+        let result = create_params().try_expires_at(too_far);
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+
+        // A millisecond timestamp passed by mistake
+        let millis = Timestamp::from_unix(now * 1000);
+        let result = create_params().try_expires_at(millis);
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_try_expires_at_valid() {
+        let expires_at = Timestamp::from_unix(Timestamp::now().as_unix() + 3600);
+        let params = create_params().try_expires_at(expires_at).unwrap();
+        assert_eq!(params.expires_at, Some(expires_at));
+    }
+
+    fn checkout_session_json(customer: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": "cs_1",
+            "customer": customer,
+            "status": "completed",
+            "currency": "PHP",
+            "line_items": [],
+            "livemode": false,
+            "url": "http://url",
+            "created_at": 654_321,
+            "updated_at": 654_322,
+        })
+    }
+
+    fn payment_intent_json() -> serde_json::Value {
+        serde_json::json!({
+            "id": "pi_123",
+            "amount": 1000,
+            "amount_received": 1000,
+            "amount_capturable": 0,
+            "client_secret": "pi_123_secret",
+            "currency": "PHP",
+            "livemode": false,
+            "payment_methods": ["card"],
+            "statement_descriptor": null,
+            "status": "succeeded",
+            "created_at": 654_321,
+            "updated_at": 654_322,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_ensure_payment_intent_uses_embedded_intent() {
+        let server = wiremock::MockServer::start().await;
+        let mut json = checkout_session_json(serde_json::Value::Null);
+        json["payment_intent"] = payment_intent_json();
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+
+        let intent = session
+            .ensure_payment_intent(&mock_client(&server))
+            .await
+            .unwrap();
+        assert_eq!(intent.id.as_str(), "pi_123");
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_payment_intent_fetches_session() {
+        use wiremock::matchers::{body_string, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let mut retrieved = checkout_session_json(serde_json::Value::Null);
+        retrieved["payment_intent"] = payment_intent_json();
+        Mock::given(method("GET"))
+            .and(path("/checkout_sessions/cs_1"))
+            .and(body_string("expand%5B%5D=payment_intent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(retrieved))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let session: CheckoutSession =
+            serde_json::from_value(checkout_session_json(serde_json::Value::Null)).unwrap();
+        let intent = session
+            .ensure_payment_intent(&mock_client(&server))
+            .await
+            .unwrap();
+        assert_eq!(intent.id.as_str(), "pi_123");
+    }
+
+    #[tokio::test]
+    async fn test_ensure_payment_intent_missing() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(checkout_session_json(serde_json::Value::Null)),
+            )
+            .mount(&server)
+            .await;
+
+        let session: CheckoutSession =
+            serde_json::from_value(checkout_session_json(serde_json::Value::Null)).unwrap();
+        let result = session.ensure_payment_intent(&mock_client(&server)).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn test_checkout_session_customer_id_deserialization() {
+        let json = checkout_session_json(serde_json::json!("cus_123"));
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+
+        let customer = session.customer.unwrap();
+        assert!(customer.is_id());
+        assert_eq!(customer.as_id().unwrap().as_str(), "cus_123");
+    }
+
+    #[test]
+    fn test_checkout_session_customer_object_deserialization() {
+        let json = checkout_session_json(serde_json::json!({
+            "id": "cus_123",
+            "email": "user@example.com",
+            "livemode": false,
+            "name": "User",
+            "created_at": 1_609_459_200,
+            "updated_at": 1_609_459_300,
+        }));
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+
+        let customer = session.customer.unwrap();
+        assert!(customer.is_object());
+        let customer = customer.as_object().unwrap();
+        assert_eq!(customer.id.as_str(), "cus_123");
+        assert_eq!(customer.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[test]
+    fn test_checkout_session_without_customer_deserialization() {
+        let mut json = checkout_session_json(serde_json::Value::Null);
+        json.as_object_mut().unwrap().remove("customer");
+        let session: CheckoutSession = serde_json::from_value(json).unwrap();
+        assert!(session.customer.is_none());
+    }
+
     #[test]
     fn test_checkout_session_serialization() {
         let mut metadata = Metadata::new();
@@ -341,6 +865,7 @@ mod tests {
             name: "Item".to_string(),
             amount: 1000,
             quantity: 3,
+            currency: Some(Currency::PHP),
             description: Some("Desc".to_string()),
             image: Some("img".to_string()),
         };
@@ -349,8 +874,10 @@ mod tests {
             id: CheckoutSessionId::new("cs_1"),
             amount: Some(1000),
             customer_reference_id: Some("cust".to_string()),
+            customer: None,
+            customer_creation: Some(CustomerCreation::IfRequired),
             billing_details_collection: Some("always".to_string()),
-            client_secret: Some("secret".to_string()),
+            client_secret: Some(Secret::from("cs_1_secret")),
             status: CheckoutSessionStatus::Active,
             currency: Currency::PHP,
             line_items: vec![line_item.clone()],
@@ -370,12 +897,15 @@ mod tests {
             updated_at: Timestamp::from_unix(654_322),
         };
 
+        assert_round_trip(&session);
+
         let json = serde_json::to_value(&session).unwrap();
         assert_eq!(json["id"], "cs_1");
         assert_eq!(json["amount"], 1000);
         assert_eq!(json["customer_reference_id"], "cust");
         assert_eq!(json["billing_details_collection"], "always");
-        assert_eq!(json["client_secret"], "secret");
+        assert_eq!(json["client_secret"], "cs_1_secret");
+        assert!(!format!("{session:?}").contains("cs_1_secret"));
         assert_eq!(json["status"], "active");
         assert_eq!(json["currency"], "PHP");
 
@@ -4,9 +4,10 @@
 //! multiple payments and billing information.
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
-    types::{Currency, CustomerId, List, ListParams, Metadata, Timestamp},
+    resources::paths,
+    types::{Currency, CustomerId, List, ListParams, Metadata, Patch, Timestamp},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -22,28 +23,49 @@ impl Customers {
         Self { http }
     }
 
-    pub async fn create(&self, params: CreateCustomer) -> Result<Customer> {
-        self.http.post("/customers", &params).await
+    pub async fn create(&self, mut params: CreateCustomer) -> Result<Customer> {
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post(paths::collection::<CustomerId>(), &params)
+            .await
     }
 
     pub async fn retrieve(&self, id: &CustomerId) -> Result<Customer> {
-        self.http.get(&format!("/customers/{}", id.as_str())).await
+        self.http.get(&paths::resource(id)).await
     }
 
+    /// Updates a customer.
+    ///
+    /// PayRex rejects currency changes for customers that already have billing statements; use
+    /// [`UpdateCustomer::validate_for`] to check for that before sending.
     pub async fn update(&self, id: &CustomerId, params: UpdateCustomer) -> Result<Customer> {
-        self.http
-            .patch(&format!("/customers/{}", id.as_str()), &params)
-            .await
+        self.http.patch(&paths::resource(id), &params).await
     }
 
     pub async fn delete(&self, id: &CustomerId) -> Result<()> {
+        self.http.delete(&paths::resource(id)).await
+    }
+
+    /// Lists customers.
+    ///
+    /// The `name` and `email` filters of [`CustomerListParams`] are exact matches. PayRex has no
+    /// customer search endpoint, so partial matching has to be done on your side.
+    ///
+    /// Endpoint: `GET /customers`
+    pub async fn list(&self, params: Option<CustomerListParams>) -> Result<List<Customer>> {
+        if let Some(params) = &params {
+            params.list_params.validate()?;
+        }
         self.http
-            .delete(&format!("/customers/{}", id.as_str()))
+            .get_with_params(paths::collection::<CustomerId>(), &params)
             .await
     }
 
-    pub async fn list(&self, params: Option<CustomerListParams>) -> Result<List<Customer>> {
-        self.http.get_with_params("/customers", &params).await
+    /// Returns the first customer whose email is exactly `email`, or `None` if there is none.
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<Customer>> {
+        let mut params = CustomerListParams::new().email(email);
+        params.list_params = ListParams::new().limit(1);
+        Ok(self.list(Some(params)).await?.into_iter().next())
     }
 }
 
@@ -56,9 +78,13 @@ pub struct Customer {
     pub currency: Option<Currency>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// The customer's mobile number, used to pre-fill e-wallet payment flows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -76,9 +102,16 @@ pub struct OptionalCustomer {
     pub currency: Option<Currency>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::types::lenient_bool::option::deserialize"
+    )]
     pub livemode: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// The customer's mobile number, used to pre-fill e-wallet payment flows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -91,9 +124,14 @@ pub struct OptionalCustomer {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateCustomer {
+    /// The currency the customer is billed in. This is required when creating a customer and
+    /// cannot be changed once the customer has billing statements.
     pub currency: Currency,
     pub email: String,
     pub name: String,
+    /// The customer's mobile number. See [`CreateCustomer::try_phone`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub billing_statement_prefix: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,8 +142,10 @@ pub struct CreateCustomer {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateCustomer {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub billing_statement_prefix: Option<String>,
+    /// The prefix of the customer's billing statement numbers. Can be cleared with
+    /// [`Patch::Clear`].
+    #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+    pub billing_statement_prefix: Patch<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_billing_statement_sequence_number: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -114,6 +154,9 @@ pub struct UpdateCustomer {
     pub email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// The customer's mobile number. See [`UpdateCustomer::try_phone`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
 }
@@ -141,6 +184,20 @@ impl CreateCustomer {
         }
     }
 
+    /// Sets `phone` without validation. Prefer `try_phone` for user input.
+    pub fn phone(mut self, phone: impl Into<String>) -> Self {
+        self.phone = Some(phone.into());
+        self
+    }
+
+    /// Sets `phone` after checking it with [`validate_phone`].
+    pub fn try_phone(mut self, phone: impl Into<String>) -> Result<Self> {
+        let phone = phone.into();
+        validate_phone(&phone)?;
+        self.phone = Some(phone);
+        Ok(self)
+    }
+
     pub fn billing_statement_prefix(mut self, billing_statement_prefix: impl Into<String>) -> Self {
         self.billing_statement_prefix = Some(billing_statement_prefix.into());
         self
@@ -154,8 +211,8 @@ impl CreateCustomer {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 }
@@ -182,8 +239,28 @@ impl UpdateCustomer {
         self
     }
 
+    /// Sets `phone` without validation. Prefer `try_phone` for user input.
+    pub fn phone(mut self, phone: impl Into<String>) -> Self {
+        self.phone = Some(phone.into());
+        self
+    }
+
+    /// Sets `phone` after checking it with [`validate_phone`].
+    pub fn try_phone(mut self, phone: impl Into<String>) -> Result<Self> {
+        let phone = phone.into();
+        validate_phone(&phone)?;
+        self.phone = Some(phone);
+        Ok(self)
+    }
+
     pub fn billing_statement_prefix(mut self, billing_statement_prefix: impl Into<String>) -> Self {
-        self.billing_statement_prefix = Some(billing_statement_prefix.into());
+        self.billing_statement_prefix = Patch::Set(billing_statement_prefix.into());
+        self
+    }
+
+    /// Removes the customer's billing statement prefix.
+    pub fn clear_billing_statement_prefix(mut self) -> Self {
+        self.billing_statement_prefix = Patch::Clear;
         self
     }
 
@@ -195,10 +272,27 @@ impl UpdateCustomer {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
+
+    /// Checks the currency change carried by these params against the current `customer`.
+    ///
+    /// A customer that already has billing statements cannot switch to a different currency,
+    /// nor be given one if it has none. PayRex has no endpoint to look up whether a customer
+    /// has billing statements, so `has_billing_statements` comes from the caller.
+    pub fn validate_for(&self, customer: &Customer, has_billing_statements: bool) -> Result<()> {
+        match self.currency {
+            Some(currency) if has_billing_statements && customer.currency != Some(currency) => {
+                Err(Error::invalid_request(format!(
+                    "cannot change the currency of customer {} because it has billing statements",
+                    customer.id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl CustomerListParams {
@@ -217,15 +311,29 @@ impl CustomerListParams {
         self
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 }
 
+/// Checks that `phone` is an optional leading `+` followed by 7 to 15 digits, with no spaces or
+/// separators. Both international and local formats pass, e.g. `+639171234567` or
+/// `09171234567`; this is a shape check, not full E.164 validation.
+pub fn validate_phone(phone: &str) -> Result<()> {
+    let digits = phone.strip_prefix('+').unwrap_or(phone);
+    if !(7..=15).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::invalid_request(format!(
+            "phone `{phone}` must be an optional `+` followed by 7 to 15 digits"
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::{Currency, CustomerId, ListParams, Metadata, Timestamp};
     use serde_json;
 
@@ -255,6 +363,20 @@ mod tests {
         assert_eq!(params.metadata, Some(metadata));
     }
 
+    #[test]
+    fn test_customer_metadata_conversions() {
+        let params =
+            CreateCustomer::new(Currency::PHP, "test@example.com".into(), "Test User".into())
+                .metadata([("tier", "gold"), ("source", "web")]);
+        let metadata = params.metadata.unwrap();
+        assert_eq!(metadata.get("tier"), Some("gold"));
+        assert_eq!(metadata.get("source"), Some("web"));
+
+        let map = std::collections::HashMap::from([("tier".to_string(), "gold".to_string())]);
+        let params = UpdateCustomer::new().metadata(map);
+        assert_eq!(params.metadata, Some(Metadata::with_pair("tier", "gold")));
+    }
+
     #[test]
     fn test_update_customer_builder() {
         let mut metadata = Metadata::new();
@@ -269,7 +391,10 @@ mod tests {
         assert_eq!(params.currency, Some(Currency::PHP));
         assert_eq!(params.email, Some("user@example.com".to_string()));
         assert_eq!(params.name, Some("User".to_string()));
-        assert_eq!(params.billing_statement_prefix, Some("BS".to_string()));
+        assert_eq!(
+            params.billing_statement_prefix,
+            Patch::Set("BS".to_string())
+        );
         assert_eq!(
             params.next_billing_statement_sequence_number,
             Some("003".to_string())
@@ -277,6 +402,173 @@ mod tests {
         assert_eq!(params.metadata, Some(metadata));
     }
 
+    #[tokio::test]
+    async fn test_find_by_email() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/customers"))
+            .and(body_string_contains("juan%40example.com"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{
+                    "id": "cus_123",
+                    "email": "juan@example.com",
+                    "livemode": false,
+                    "created_at": 1_609_459_200,
+                    "updated_at": 1_609_459_300,
+                }],
+                "has_more": false,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/customers"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": [], "has_more": false })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+
+        let customer = client
+            .customers()
+            .find_by_email("juan@example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(customer.id.as_str(), "cus_123");
+
+        let missing = client
+            .customers()
+            .find_by_email("maria@example.com")
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+
+        let requests = server.received_requests().await.unwrap();
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("limit=1"));
+    }
+
+    #[test]
+    fn test_customer_phone() {
+        let params = CreateCustomer::new(Currency::PHP, "a@example.com".into(), "A".into())
+            .try_phone("+639171234567")
+            .unwrap();
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["phone"], "+639171234567");
+
+        let json =
+            serde_json::to_value(UpdateCustomer::new().try_phone("09171234567").unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({ "phone": "09171234567" }));
+        assert!(
+            serde_json::to_value(UpdateCustomer::new())
+                .unwrap()
+                .get("phone")
+                .is_none()
+        );
+
+        for phone in [
+            "",
+            "+",
+            "12345",
+            "+63 917 123 4567",
+            "0917-123-4567",
+            "+1234567890123456",
+        ] {
+            assert!(
+                UpdateCustomer::new().try_phone(phone).is_err(),
+                "{phone} should be rejected"
+            );
+        }
+
+        let customer: Customer = serde_json::from_value(serde_json::json!({
+            "id": "cus_123",
+            "livemode": false,
+            "phone": "+639171234567",
+            "created_at": 1_609_459_200,
+            "updated_at": 1_609_459_300,
+        }))
+        .unwrap();
+        assert_eq!(customer.phone.as_deref(), Some("+639171234567"));
+    }
+
+    fn customer() -> Customer {
+        Customer {
+            id: CustomerId::new("cus_123456"),
+            billing_statement_prefix: None,
+            currency: Some(Currency::PHP),
+            email: None,
+            livemode: false,
+            name: None,
+            phone: None,
+            metadata: None,
+            next_billing_statement_sequence_number: None,
+            created_at: Timestamp::from_unix(1_609_459_200),
+            updated_at: Timestamp::from_unix(1_609_459_300),
+        }
+    }
+
+    #[test]
+    fn test_update_customer_billing_statement_prefix_patch() {
+        let json = serde_json::to_value(UpdateCustomer::new().name("User")).unwrap();
+        assert!(json.get("billing_statement_prefix").is_none());
+
+        let json =
+            serde_json::to_value(UpdateCustomer::new().clear_billing_statement_prefix()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "billing_statement_prefix": null })
+        );
+
+        let json =
+            serde_json::to_value(UpdateCustomer::new().billing_statement_prefix("BS")).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "billing_statement_prefix": "BS" })
+        );
+
+        let form =
+            serde_qs::to_string(&UpdateCustomer::new().clear_billing_statement_prefix()).unwrap();
+        assert_eq!(form, "billing_statement_prefix=");
+    }
+
+    #[test]
+    fn test_update_customer_validate_currency() {
+        let customer = customer();
+
+        assert!(UpdateCustomer::new().validate_for(&customer, true).is_ok());
+        assert!(
+            UpdateCustomer::new()
+                .currency(Currency::PHP)
+                .validate_for(&customer, true)
+                .is_ok()
+        );
+        assert!(
+            UpdateCustomer::new()
+                .currency(Currency::PHP)
+                .validate_for(&customer, false)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_update_customer_validate_currency_change_with_statements() {
+        let mut customer = customer();
+        customer.currency = None;
+        let params = UpdateCustomer::new().currency(Currency::PHP);
+
+        assert!(params.validate_for(&customer, false).is_ok());
+        assert!(matches!(
+            params.validate_for(&customer, true),
+            Err(Error::InvalidRequest(m)) if m.contains("billing statements")
+        ));
+    }
+
     #[test]
     fn test_customer_list_params_builder() {
         let mut metadata = Metadata::new();
@@ -308,11 +600,14 @@ mod tests {
             email: Some("test@example.com".to_string()),
             livemode: false,
             name: Some("Test User".to_string()),
+            phone: Some("+639171234567".to_string()),
             metadata: Some(metadata.clone()),
             next_billing_statement_sequence_number: Some("004".to_string()),
             created_at: Timestamp::from_unix(1_609_459_200),
             updated_at: Timestamp::from_unix(1_609_459_300),
         };
+        assert_round_trip(&customer);
+
         let json = serde_json::to_value(&customer).unwrap();
         assert_eq!(json["id"], "cus_123456");
         assert_eq!(json["billing_statement_prefix"], "PREF");
@@ -320,6 +615,7 @@ mod tests {
         assert_eq!(json["email"], "test@example.com");
         assert_eq!(json["livemode"], false);
         assert_eq!(json["name"], "Test User");
+        assert_eq!(json["phone"], "+639171234567");
         assert_eq!(json["metadata"]["order_id"], "12345");
         assert_eq!(json["next_billing_statement_sequence_number"], "004");
         assert_eq!(json["created_at"], 1_609_459_200);
@@ -344,4 +640,23 @@ mod tests {
         assert_eq!(json["name"], "User Name");
         assert_eq!(json["metadata"]["foo"], "bar");
     }
+
+    #[test]
+    fn test_optional_customer_round_trip() {
+        let customer = OptionalCustomer {
+            id: CustomerId::new("cus_123456"),
+            billing_statement_prefix: None,
+            currency: Some(Currency::PHP),
+            email: Some("test@example.com".to_string()),
+            livemode: None,
+            name: None,
+            phone: None,
+            metadata: None,
+            next_billing_statement_sequence_number: None,
+            created_at: Some(Timestamp::from_unix(1_609_459_200)),
+            updated_at: None,
+        };
+
+        assert_round_trip(&customer);
+    }
 }
@@ -7,6 +7,7 @@ pub mod billing_statement_line_items;
 pub mod billing_statements;
 pub mod checkout_sessions;
 pub mod customers;
+pub(crate) mod paths;
 pub mod payment_intents;
 pub mod payments;
 pub mod payouts;
@@ -0,0 +1,135 @@
+//! Endpoint paths built from typed IDs.
+//!
+//! Every resource path is derived from the collection registered for its ID type here, so a
+//! resource method cannot pair an ID with the wrong collection or drop a segment.
+
+use crate::types::{
+    BillingStatementId, BillingStatementLineItemId, CheckoutSessionId, CustomerId, PaymentId,
+    PaymentIntentId, PayoutId, RefundId, WebhookId,
+};
+
+/// An ID whose resource lives under a fixed API collection.
+pub(crate) trait ResourcePath {
+    /// The collection path, e.g. `/payment_intents`.
+    const COLLECTION: &'static str;
+
+    /// The ID as it appears in the path.
+    fn path_segment(&self) -> &str;
+}
+
+macro_rules! impl_resource_path {
+    ($($id:ty => $collection:literal),* $(,)?) => {
+        $(
+            impl ResourcePath for $id {
+                const COLLECTION: &'static str = $collection;
+
+                fn path_segment(&self) -> &str {
+                    self.as_str()
+                }
+            }
+        )*
+    };
+}
+
+impl_resource_path! {
+    BillingStatementId => "/billing_statements",
+    BillingStatementLineItemId => "/billing_statement_line_items",
+    CheckoutSessionId => "/checkout_sessions",
+    CustomerId => "/customers",
+    PaymentId => "/payments",
+    PaymentIntentId => "/payment_intents",
+    PayoutId => "/payouts",
+    RefundId => "/refunds",
+    WebhookId => "/webhooks",
+}
+
+/// Returns the collection path for `I`, e.g. `/customers`.
+pub(crate) const fn collection<I: ResourcePath>() -> &'static str {
+    I::COLLECTION
+}
+
+/// Returns the path of a single resource, e.g. `/customers/cus_123`.
+pub(crate) fn resource<I: ResourcePath>(id: &I) -> String {
+    format!("{}/{}", I::COLLECTION, id.path_segment())
+}
+
+/// Returns the path of an action or sub-resource of a resource, e.g.
+/// `/payment_intents/pi_123/cancel` or `/payouts/po_123/transactions`.
+pub(crate) fn action<I: ResourcePath>(id: &I, action: &str) -> String {
+    format!("{}/{}/{action}", I::COLLECTION, id.path_segment())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_paths() {
+        let cases = [
+            (
+                resource(&BillingStatementId::new("bstm_1")),
+                "/billing_statements/bstm_1",
+            ),
+            (
+                resource(&BillingStatementLineItemId::new("bstm_li_1")),
+                "/billing_statement_line_items/bstm_li_1",
+            ),
+            (
+                resource(&CheckoutSessionId::new("cs_1")),
+                "/checkout_sessions/cs_1",
+            ),
+            (resource(&CustomerId::new("cus_1")), "/customers/cus_1"),
+            (resource(&PaymentId::new("pay_1")), "/payments/pay_1"),
+            (
+                resource(&PaymentIntentId::new("pi_1")),
+                "/payment_intents/pi_1",
+            ),
+            (resource(&PayoutId::new("po_1")), "/payouts/po_1"),
+            (resource(&RefundId::new("re_1")), "/refunds/re_1"),
+            (resource(&WebhookId::new("wh_1")), "/webhooks/wh_1"),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(path, expected);
+        }
+    }
+
+    #[test]
+    fn test_action_paths() {
+        let statement = BillingStatementId::new("bstm_1");
+        assert_eq!(
+            action(&statement, "finalize"),
+            "/billing_statements/bstm_1/finalize"
+        );
+        assert_eq!(
+            action(&statement, "mark_uncollectible"),
+            "/billing_statements/bstm_1/mark_uncollectible"
+        );
+        assert_eq!(
+            action(&PaymentIntentId::new("pi_1"), "capture"),
+            "/payment_intents/pi_1/capture"
+        );
+        assert_eq!(
+            action(&CheckoutSessionId::new("cs_1"), "expire"),
+            "/checkout_sessions/cs_1/expire"
+        );
+        assert_eq!(
+            action(&PayoutId::new("po_1"), "transactions"),
+            "/payouts/po_1/transactions"
+        );
+        assert_eq!(
+            action(&WebhookId::new("wh_1"), "enable"),
+            "/webhooks/wh_1/enable"
+        );
+    }
+
+    #[test]
+    fn test_collection_paths() {
+        assert_eq!(collection::<CustomerId>(), "/customers");
+        assert_eq!(collection::<PaymentIntentId>(), "/payment_intents");
+        assert_eq!(
+            collection::<BillingStatementLineItemId>(),
+            "/billing_statement_line_items"
+        );
+    }
+}
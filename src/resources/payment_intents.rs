@@ -4,10 +4,16 @@
 //! They track the lifecycle of a payment from creation through completion.
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
+    resources::{
+        checkout_sessions::CheckoutSession,
+        paths,
+        payments::{Payment, Payments},
+    },
     types::{
-        CaptureMethod, Currency, Metadata, PaymentIntentId, PaymentMethod, PaymentMethodOptions,
+        CaptureMethod, ConfirmationMethod, Currency, CustomerId, Metadata, PaymentId,
+        PaymentIntentId, PaymentMethod, PaymentMethodOptions, Secret, SetupFutureUsage, Snapshot,
         Timestamp,
     },
 };
@@ -34,8 +40,37 @@ impl PaymentIntents {
     /// Endpoint: `POST /payment_intents`
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/payment_intents/create)
-    pub async fn create(&self, params: CreatePaymentIntent) -> Result<PaymentIntent> {
-        self.http.post("/payment_intents", &params).await
+    pub async fn create(&self, mut params: CreatePaymentIntent) -> Result<PaymentIntent> {
+        params.validate()?;
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post(paths::collection::<PaymentIntentId>(), &params)
+            .await
+    }
+
+    /// Creates a [`PaymentIntent`] resource, tagging the request with an idempotency key.
+    ///
+    /// If the returned future is dropped before a response arrives, the request may still have
+    /// been processed. Re-issuing the create with the same `idempotency_key` is safe: PayRex
+    /// returns the original result instead of creating a second [`PaymentIntent`].
+    ///
+    /// Endpoint: `POST /payment_intents`
+    ///
+    /// [API Reference](https://docs.payrexhq.com/docs/api/payment_intents/create)
+    pub async fn create_with_idempotency_key(
+        &self,
+        mut params: CreatePaymentIntent,
+        idempotency_key: &str,
+    ) -> Result<PaymentIntent> {
+        params.validate()?;
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post_idempotent(
+                paths::collection::<PaymentIntentId>(),
+                &params,
+                idempotency_key,
+            )
+            .await
     }
 
     /// Retrieve a [`PaymentIntent`] resource by ID.
@@ -44,9 +79,7 @@ impl PaymentIntents {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/payment_intents/retrieve)
     pub async fn retrieve(&self, id: &PaymentIntentId) -> Result<PaymentIntent> {
-        self.http
-            .get(&format!("/payment_intents/{}", id.as_str()))
-            .await
+        self.http.get(&paths::resource(id)).await
     }
 
     /// Cancels a [`PaymentIntent`] resource. A payment intent with a status of `canceled` means your
@@ -56,9 +89,7 @@ impl PaymentIntents {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/payment_intents/cancel)
     pub async fn cancel(&self, id: &PaymentIntentId) -> Result<PaymentIntent> {
-        self.http
-            .post(&format!("/payment_intents/{}/cancel", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "cancel"), &()).await
     }
 
     /// Captures a [`PaymentIntent`] resource.
@@ -71,12 +102,22 @@ impl PaymentIntents {
         id: &PaymentIntentId,
         params: CapturePaymentIntent,
     ) -> Result<PaymentIntent> {
-        self.http
-            .post(
-                &format!("/payment_intents/{}/capture", id.as_str()),
-                &params,
-            )
+        self.http.post(&paths::action(id, "capture"), &params).await
+    }
+
+    /// Retrieves the latest [`Payment`] created by `intent`, or `None` if the intent has no
+    /// successful payment yet.
+    ///
+    /// Endpoint: `GET /payments/:id`
+    pub async fn retrieve_latest_payment(&self, intent: &PaymentIntent) -> Result<Option<Payment>> {
+        let Some(payment_id) = &intent.latest_payment else {
+            return Ok(None);
+        };
+
+        Payments::new(Arc::clone(&self.http))
+            .retrieve(&PaymentId::new_unchecked(payment_id.as_str()))
             .await
+            .map(Some)
     }
 }
 
@@ -139,11 +180,14 @@ pub struct PaymentIntent {
     ///
     /// The minimum amount is ₱ 20 (2000 in cents) and the maximum amount is ₱ 59,999,999.99
     /// (5999999999 in cents).
+    ///
+    /// Also accepts `capturable_amount`, the field name used by older payloads.
+    #[serde(alias = "capturable_amount")]
     pub amount_capturable: i64,
 
     ///The client secret of this [`PaymentIntent`] used for client-side retrieval using a public API
     ///key. The client secret can be used to complete a payment from your client application.
-    pub client_secret: String,
+    pub client_secret: Secret<String>,
 
     /// A three-letter ISO currency code in uppercase. As of the moment, we only support PHP.
     pub currency: Currency,
@@ -154,6 +198,7 @@ pub struct PaymentIntent {
     pub description: Option<String>,
 
     /// The value is `true` if the resource's mode is live or the value is `false` if the resource mode is test.
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
 
     /// A set of key-value pairs attached to the [`PaymentIntent`] and the resources created by the
@@ -206,13 +251,103 @@ pub struct PaymentIntent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capture_before_at: Option<Timestamp>,
 
+    /// Whether the [`PaymentIntent`] is confirmed automatically or by your server. See
+    /// [`CreatePaymentIntent::confirmation_method`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation_method: Option<ConfirmationMethod>,
+
     /// The time the resource was created and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `created`, the field name used by older payloads.
+    #[serde(alias = "created")]
     pub created_at: Timestamp,
 
     /// The time the resource was updated and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `updated`, the field name used by older payloads.
+    #[serde(alias = "updated")]
     pub updated_at: Timestamp,
 }
 
+impl PaymentIntent {
+    /// Returns [`PaymentIntent::amount`] formatted in the intent's currency, e.g. `₱120.50`.
+    #[must_use]
+    pub fn formatted_amount(&self) -> String {
+        self.currency.format_amount(self.amount)
+    }
+
+    /// Returns `true` if this [`PaymentIntent`] is consistent with `session`.
+    ///
+    /// The amount, currency, and livemode must match, and if the session embeds a payment
+    /// intent, it must be this one. Check this before fulfilling an order from a completed
+    /// checkout session to avoid acting on a mismatched or stale session.
+    #[must_use]
+    pub fn matches_checkout(&self, session: &CheckoutSession) -> bool {
+        let same_amount = session
+            .amount
+            .is_some_and(|amount| i64::try_from(amount) == Ok(self.amount));
+        let same_intent = session.payment_intent_id().is_none_or(|id| *id == self.id);
+
+        same_amount
+            && same_intent
+            && session.currency == self.currency
+            && session.livemode == self.livemode
+    }
+
+    /// Returns the time by which this intent must be captured before PayRex cancels it.
+    ///
+    /// Only set for authorized intents awaiting a manual capture.
+    #[must_use]
+    pub const fn capture_deadline(&self) -> Option<Timestamp> {
+        self.capture_before_at
+    }
+
+    /// Returns how long is left until [`PaymentIntent::capture_deadline`], negative once it has
+    /// passed.
+    #[must_use]
+    pub fn time_until_capture_deadline(&self) -> Option<chrono::Duration> {
+        self.time_until_capture_deadline_at(Timestamp::now())
+    }
+
+    /// Like [`PaymentIntent::time_until_capture_deadline`], measured from `now`.
+    #[must_use]
+    pub fn time_until_capture_deadline_at(&self, now: Timestamp) -> Option<chrono::Duration> {
+        self.capture_before_at
+            .map(|deadline| deadline.to_datetime() - now.to_datetime())
+    }
+
+    /// Returns `true` if the capture deadline is at most `within` away or has already passed.
+    ///
+    /// Intents without a capture deadline are never near one.
+    #[must_use]
+    pub fn is_capture_deadline_near(&self, within: chrono::Duration) -> bool {
+        self.is_capture_deadline_near_at(within, Timestamp::now())
+    }
+
+    /// Like [`PaymentIntent::is_capture_deadline_near`], measured from `now`.
+    #[must_use]
+    pub fn is_capture_deadline_near_at(&self, within: chrono::Duration, now: Timestamp) -> bool {
+        self.time_until_capture_deadline_at(now)
+            .is_some_and(|remaining| remaining <= within)
+    }
+}
+
+impl Snapshot for PaymentIntent {
+    type Status = PaymentIntentStatus;
+
+    fn updated_at(&self) -> Timestamp {
+        self.updated_at
+    }
+
+    fn status(&self) -> Self::Status {
+        self.status
+    }
+
+    fn can_transition(from: Self::Status, to: Self::Status) -> bool {
+        from.can_transition_to(to)
+    }
+}
+
 /// All fields in this struct are optional since fields nested under billing statements have
 /// optional fields. Hence, this should not be used for regular payment intent routes.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -242,11 +377,14 @@ pub struct OptionalPaymentIntent {
     ///
     /// The minimum amount is ₱ 20 (2000 in cents) and the maximum amount is ₱ 59,999,999.99
     /// (5999999999 in cents).
+    ///
+    /// Also accepts `capturable_amount`, the field name used by older payloads.
+    #[serde(alias = "capturable_amount")]
     pub amount_capturable: Option<i64>,
 
     ///The client secret of this [`PaymentIntent`] used for client-side retrieval using a public API
     ///key. The client secret can be used to complete a payment from your client application.
-    pub client_secret: Option<String>,
+    pub client_secret: Option<Secret<String>>,
 
     /// A three-letter ISO currency code in uppercase. As of the moment, we only support PHP.
     pub currency: Option<Currency>,
@@ -257,6 +395,10 @@ pub struct OptionalPaymentIntent {
     pub description: Option<String>,
 
     /// The value is `true` if the resource's mode is live or the value is `false` if the resource mode is test.
+    #[serde(
+        default,
+        deserialize_with = "crate::types::lenient_bool::option::deserialize"
+    )]
     pub livemode: Option<bool>,
 
     /// A set of key-value pairs attached to the [`PaymentIntent`] and the resources created by the
@@ -309,10 +451,21 @@ pub struct OptionalPaymentIntent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capture_before_at: Option<Timestamp>,
 
+    /// Whether the [`PaymentIntent`] is confirmed automatically or by your server. See
+    /// [`CreatePaymentIntent::confirmation_method`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation_method: Option<ConfirmationMethod>,
+
     /// The time the resource was created and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `created`, the field name used by older payloads.
+    #[serde(alias = "created")]
     pub created_at: Option<Timestamp>,
 
     /// The time the resource was updated and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `updated`, the field name used by older payloads.
+    #[serde(alias = "updated")]
     pub updated_at: Option<Timestamp>,
 }
 
@@ -345,10 +498,50 @@ pub enum PaymentIntentStatus {
     Succeeded,
 }
 
+impl PaymentIntentStatus {
+    /// Returns `true` if a payment intent in this status can move to `next`.
+    ///
+    /// Encodes the lifecycle from the PayRex docs: a failed attempt sends the intent back to
+    /// waiting for a payment method, an authorized payment waits in
+    /// [`PaymentIntentStatus::RequiresCapture`] until it is captured or cancelled, and
+    /// [`PaymentIntentStatus::Succeeded`] and [`PaymentIntentStatus::Canceled`] are final.
+    /// Staying in the same status is always allowed.
+    #[must_use]
+    pub const fn can_transition_to(self, next: Self) -> bool {
+        match self {
+            Self::AwaitingPaymentMethod
+            | Self::RequiresPaymentMethod
+            | Self::RequiresConfirmation
+            | Self::RequiresAction => true,
+            Self::Processing => matches!(
+                next,
+                Self::Processing
+                    | Self::AwaitingPaymentMethod
+                    | Self::RequiresPaymentMethod
+                    | Self::RequiresAction
+                    | Self::RequiresCapture
+                    | Self::Succeeded
+            ),
+            Self::RequiresCapture => {
+                matches!(
+                    next,
+                    Self::RequiresCapture | Self::Succeeded | Self::Canceled
+                )
+            }
+            Self::Canceled => matches!(next, Self::Canceled),
+            Self::Succeeded => matches!(next, Self::Succeeded),
+        }
+    }
+}
+
 /// Query parameters when creating a payment intent.
 ///
+/// `amount` and `payment_methods` are required. The [`Default`] value leaves them unset, which
+/// is handy for fixtures (`CreatePaymentIntent { amount: 10000, ..Default::default() }`) but is
+/// rejected by [`CreatePaymentIntent::validate`] until they are filled in.
+///
 /// [Reference](https://docs.payrexhq.com/docs/api/payment_intents/create#parameters)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreatePaymentIntent {
     /// The amount to be collected by the [`PaymentIntent`]. This is a positive integer your customer
     /// will pay in the smallest currency unit, cents. If the customer should pay ₱ 120.50, the
@@ -400,6 +593,20 @@ pub struct CreatePaymentIntent {
     /// didn't exit or close their browser while authenticating.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub return_url: Option<String>,
+
+    /// The ID of the customer the [`PaymentIntent`] belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_id: Option<CustomerId>,
+
+    /// Indicates that the payment method used should be saved for future payments of the
+    /// customer, e.g. for subscriptions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_future_usage: Option<SetupFutureUsage>,
+
+    /// Whether the [`PaymentIntent`] is confirmed automatically once a payment method is
+    /// attached, or waits for your server to confirm it. Defaults to automatic confirmation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_method: Option<ConfirmationMethod>,
 }
 
 /// Query parameters when capturing a payment intent.
@@ -439,6 +646,9 @@ impl CreatePaymentIntent {
             payment_method_options: None,
             statement_descriptor: None,
             return_url: None,
+            customer_id: None,
+            setup_future_usage: None,
+            confirmation_method: None,
         }
     }
 
@@ -451,8 +661,8 @@ impl CreatePaymentIntent {
 
     /// Sets the metadata.
     #[must_use]
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 
@@ -483,12 +693,54 @@ impl CreatePaymentIntent {
         self.return_url = Some(url.into());
         self
     }
+
+    /// Sets the customer ID.
+    #[must_use]
+    pub fn customer_id(mut self, customer_id: CustomerId) -> Self {
+        self.customer_id = Some(customer_id);
+        self
+    }
+
+    /// Sets the setup future usage.
+    #[must_use]
+    pub const fn setup_future_usage(mut self, usage: SetupFutureUsage) -> Self {
+        self.setup_future_usage = Some(usage);
+        self
+    }
+
+    /// Sets whether the payment intent is confirmed automatically or by your server.
+    #[must_use]
+    pub const fn confirmation_method(mut self, method: ConfirmationMethod) -> Self {
+        self.confirmation_method = Some(method);
+        self
+    }
+
+    /// Checks that the required fields are set and that `amount` is within
+    /// [`Currency::validate_amount`]'s bounds.
+    ///
+    /// Called by [`PaymentIntents::create`] before the request is sent.
+    pub fn validate(&self) -> Result<()> {
+        self.currency.validate_amount(self.amount)?;
+        if self.payment_methods.is_empty() {
+            return Err(Error::invalid_request(
+                "payment_methods must contain at least one payment method",
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client, payment_json};
     use crate::types::CardOptions;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
 
     #[test]
     fn test_create_payment_intent_builder() {
@@ -545,12 +797,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_payment_intent_customer_and_setup_future_usage() {
+        let params = CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card])
+            .customer_id(CustomerId::new("cus_123"))
+            .setup_future_usage(SetupFutureUsage::OffSession);
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["customer_id"], "cus_123");
+        assert_eq!(json["setup_future_usage"], "off_session");
+
+        let params = CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card]);
+        let json = serde_json::to_value(&params).unwrap();
+        assert!(json.get("customer_id").is_none());
+        assert!(json.get("setup_future_usage").is_none());
+    }
+
+    #[test]
+    fn test_create_payment_intent_confirmation_method() {
+        let params = CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card]);
+        let json = serde_json::to_value(&params).unwrap();
+        assert!(json.get("confirmation_method").is_none());
+
+        for (method, expected) in [
+            (ConfirmationMethod::Automatic, "automatic"),
+            (ConfirmationMethod::Manual, "manual"),
+        ] {
+            let params = params.clone().confirmation_method(method);
+            let json = serde_json::to_value(&params).unwrap();
+            assert_eq!(json["confirmation_method"], expected);
+        }
+
+        let mut intent = serde_json::to_value(payment_intent("pi_123", 10000)).unwrap();
+        assert_eq!(
+            serde_json::from_value::<PaymentIntent>(intent.clone())
+                .unwrap()
+                .confirmation_method,
+            None
+        );
+        intent["confirmation_method"] = "manual".into();
+        let intent: PaymentIntent = serde_json::from_value(intent).unwrap();
+        assert_eq!(intent.confirmation_method, Some(ConfirmationMethod::Manual));
+    }
+
+    #[test]
+    fn test_create_payment_intent_metadata_conversions() {
+        let params = CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card])
+            .metadata([("order_id", "12345")]);
+        assert_eq!(
+            params.metadata,
+            Some(Metadata::with_pair("order_id", "12345"))
+        );
+
+        let map = std::collections::HashMap::from([("order_id".to_string(), "12345".to_string())]);
+        let params =
+            CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card]).metadata(map);
+        assert_eq!(
+            params.metadata,
+            Some(Metadata::with_pair("order_id", "12345"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_create_is_rejected_before_sending() {
+        let server = MockServer::start().await;
+        let intents = mock_client(&server).payment_intents();
+
+        let result = intents.create(CreatePaymentIntent::default()).await;
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("amount")));
+
+        let params = CreatePaymentIntent {
+            amount: 10000,
+            ..Default::default()
+        };
+        let result = intents.create(params).await;
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("payment_methods")));
+
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_rejects_amount_below_minimum() {
+        let params = CreatePaymentIntent::new(1500, Currency::PHP, &[PaymentMethod::Card]);
+        let result = params.validate();
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("₱20.00")));
+
+        let params = CreatePaymentIntent::new(2000, Currency::PHP, &[PaymentMethod::Card]);
+        assert!(params.validate().is_ok());
+    }
+
     #[test]
     fn test_capture_payment_intent() {
         let params = CapturePaymentIntent::new(5000);
         assert_eq!(params.amount, 5000);
     }
 
+    #[test]
+    fn test_payment_intent_status_transitions() {
+        use PaymentIntentStatus::*;
+
+        for next in [
+            RequiresAction,
+            Processing,
+            RequiresCapture,
+            Succeeded,
+            Canceled,
+        ] {
+            assert!(AwaitingPaymentMethod.can_transition_to(next));
+            assert!(RequiresConfirmation.can_transition_to(next));
+        }
+        assert!(RequiresAction.can_transition_to(AwaitingPaymentMethod));
+
+        assert!(Processing.can_transition_to(Succeeded));
+        assert!(Processing.can_transition_to(RequiresCapture));
+        assert!(Processing.can_transition_to(AwaitingPaymentMethod));
+        assert!(!Processing.can_transition_to(Canceled));
+        assert!(!Processing.can_transition_to(RequiresConfirmation));
+
+        assert!(RequiresCapture.can_transition_to(Succeeded));
+        assert!(RequiresCapture.can_transition_to(Canceled));
+        assert!(!RequiresCapture.can_transition_to(Processing));
+        assert!(!RequiresCapture.can_transition_to(AwaitingPaymentMethod));
+
+        for next in [AwaitingPaymentMethod, Processing, RequiresCapture, Canceled] {
+            assert!(!Succeeded.can_transition_to(next));
+        }
+        for next in [
+            AwaitingPaymentMethod,
+            Processing,
+            RequiresCapture,
+            Succeeded,
+        ] {
+            assert!(!Canceled.can_transition_to(next));
+        }
+        assert!(Succeeded.can_transition_to(Succeeded));
+        assert!(Canceled.can_transition_to(Canceled));
+    }
+
     #[test]
     fn test_payment_intent_status_serialization() {
         use serde_json;
@@ -564,6 +947,86 @@ mod tests {
         assert_eq!(json, "\"succeeded\"");
     }
 
+    /// Mimics PayRex's idempotency handling: the first request for a key creates a resource
+    /// (slowly), later requests with the same key replay the stored response.
+    struct IdempotentCreate {
+        requests: Arc<AtomicUsize>,
+        created: Arc<Mutex<HashSet<String>>>,
+    }
+
+    impl Respond for IdempotentCreate {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let key = request
+                .headers
+                .get(crate::http::IDEMPOTENCY_KEY_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+
+            let body = serde_json::json!({
+                "id": "pi_123",
+                "amount": 10000,
+                "amount_received": 0,
+                "amount_capturable": 0,
+                "client_secret": "pi_123_secret",
+                "currency": "PHP",
+                "livemode": false,
+                "payment_methods": ["card"],
+                "statement_descriptor": null,
+                "status": "awaiting_payment_method",
+                "created_at": 1_700_000_000,
+                "updated_at": 1_700_000_000,
+            });
+
+            let first = self.created.lock().unwrap().insert(key);
+            let response = ResponseTemplate::new(200).set_body_json(body);
+            if first {
+                response.set_delay(Duration::from_millis(500))
+            } else {
+                response
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_create_is_safe_to_reissue_with_same_key() {
+        let server = MockServer::start().await;
+        let requests = Arc::new(AtomicUsize::new(0));
+        let created = Arc::new(Mutex::new(HashSet::new()));
+        Mock::given(method("POST"))
+            .and(path("/payment_intents"))
+            .respond_with(IdempotentCreate {
+                requests: Arc::clone(&requests),
+                created: Arc::clone(&created),
+            })
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let params = CreatePaymentIntent::new(10000, Currency::PHP, &[PaymentMethod::Card]);
+
+        // Drop the first create while the request is in flight.
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(100),
+            client
+                .payment_intents()
+                .create_with_idempotency_key(params.clone(), "order-1"),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        let intent = client
+            .payment_intents()
+            .create_with_idempotency_key(params, "order-1")
+            .await
+            .unwrap();
+
+        assert_eq!(intent.id.as_str(), "pi_123");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(created.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_payment_methods_in_create_intent() {
         use PaymentMethod::*;
@@ -579,4 +1042,252 @@ mod tests {
         assert_eq!(methods[1].as_str().unwrap(), "gcash");
         assert_eq!(methods[2].as_str().unwrap(), "maya");
     }
+
+    #[test]
+    fn test_payment_intent_round_trip() {
+        let mut metadata = Metadata::new();
+        metadata.insert("order_id", "12345");
+
+        let intent = PaymentIntent {
+            id: PaymentIntentId::new("pi_123"),
+            amount: 10000,
+            amount_received: 0,
+            amount_capturable: 10000,
+            client_secret: Secret::from("pi_123_secret"),
+            currency: Currency::PHP,
+            description: Some("Order #12345".to_string()),
+            livemode: false,
+            metadata: Some(metadata),
+            latest_payment: None,
+            last_payment_error: Some(PaymentError {
+                code: Some("card_declined".to_string()),
+                message: None,
+                param: None,
+            }),
+            payment_method_id: None,
+            payment_methods: vec!["card".to_string()],
+            payment_method_options: None,
+            statement_descriptor: None,
+            status: PaymentIntentStatus::RequiresCapture,
+            next_action: Some(NextAction {
+                action_type: "redirect".to_string(),
+                redirect_url: Some("https://example.com/3ds".to_string()),
+            }),
+            return_url: None,
+            capture_before_at: Some(Timestamp::from_unix(1_700_600_000)),
+            confirmation_method: Some(ConfirmationMethod::Manual),
+            created_at: Timestamp::from_unix(1_700_000_000),
+            updated_at: Timestamp::from_unix(1_700_000_100),
+        };
+
+        assert_round_trip(&intent);
+        assert!(!format!("{intent:?}").contains("pi_123_secret"));
+    }
+
+    fn checkout_session(amount: u64, livemode: bool) -> CheckoutSession {
+        serde_json::from_value(serde_json::json!({
+            "id": "cs_123",
+            "amount": amount,
+            "status": "completed",
+            "currency": "PHP",
+            "line_items": [],
+            "livemode": livemode,
+            "url": "https://checkout.payrexhq.com/c/cs_123",
+            "created_at": 1_700_000_000,
+            "updated_at": 1_700_000_100,
+        }))
+        .unwrap()
+    }
+
+    fn payment_intent(id: &str, amount: i64) -> PaymentIntent {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "amount": amount,
+            "amount_received": amount,
+            "amount_capturable": 0,
+            "client_secret": "pi_secret",
+            "currency": "PHP",
+            "livemode": false,
+            "payment_methods": ["card"],
+            "statement_descriptor": null,
+            "status": "succeeded",
+            "created_at": 1_700_000_000,
+            "updated_at": 1_700_000_100,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_payment_intent_is_stale_relative_to() {
+        let snapshot = |status: &str, updated_at: i64| -> PaymentIntent {
+            let mut intent = serde_json::to_value(payment_intent("pi_123", 10000)).unwrap();
+            intent["status"] = status.into();
+            intent["updated_at"] = updated_at.into();
+            serde_json::from_value(intent).unwrap()
+        };
+
+        // The poll saw the intent succeed, then the older webhook arrives late.
+        let polled = snapshot("succeeded", 1_700_000_200);
+        let late_event = snapshot("processing", 1_700_000_100);
+        assert!(late_event.is_stale_relative_to(&polled));
+        assert!(!polled.is_stale_relative_to(&late_event));
+
+        // Same second: the status further along the lifecycle wins.
+        let processing = snapshot("processing", 1_700_000_100);
+        let succeeded = snapshot("succeeded", 1_700_000_100);
+        assert!(processing.is_stale_relative_to(&succeeded));
+        assert!(!succeeded.is_stale_relative_to(&processing));
+        assert!(!succeeded.is_stale_relative_to(&succeeded.clone()));
+    }
+
+    #[test]
+    fn test_payment_intent_accepts_legacy_field_names() {
+        let intent: PaymentIntent = serde_json::from_value(serde_json::json!({
+            "id": "pi_123",
+            "amount": 10000,
+            "amount_received": 0,
+            "capturable_amount": 10000,
+            "client_secret": "pi_secret",
+            "currency": "PHP",
+            "livemode": false,
+            "payment_methods": ["card"],
+            "status": "requires_capture",
+            "created": 1_700_000_000,
+            "updated": 1_700_000_100,
+        }))
+        .unwrap();
+
+        assert_eq!(intent.amount_capturable, 10000);
+        assert_eq!(intent.created_at, Timestamp::from_unix(1_700_000_000));
+        assert_eq!(intent.updated_at, Timestamp::from_unix(1_700_000_100));
+
+        let json = serde_json::to_value(&intent).unwrap();
+        assert_eq!(json["amount_capturable"], 10000);
+        assert!(json.get("capturable_amount").is_none());
+    }
+
+    #[test]
+    fn test_capture_deadline() {
+        let now = Timestamp::from_unix(1_700_000_000);
+        let mut intent = payment_intent("pi_123", 10000);
+
+        assert_eq!(intent.capture_deadline(), None);
+        assert_eq!(intent.time_until_capture_deadline_at(now), None);
+        assert!(!intent.is_capture_deadline_near_at(chrono::Duration::days(7), now));
+
+        intent.status = PaymentIntentStatus::RequiresCapture;
+        intent.capture_before_at = Some(Timestamp::from_unix(1_700_000_000 + 2 * 3600));
+
+        assert_eq!(
+            intent.capture_deadline(),
+            Some(Timestamp::from_unix(1_700_007_200))
+        );
+        assert_eq!(
+            intent.time_until_capture_deadline_at(now),
+            Some(chrono::Duration::hours(2))
+        );
+        assert!(intent.is_capture_deadline_near_at(chrono::Duration::hours(2), now));
+        assert!(!intent.is_capture_deadline_near_at(chrono::Duration::hours(1), now));
+
+        let later = Timestamp::from_unix(1_700_010_000);
+        assert!(intent.time_until_capture_deadline_at(later).unwrap() < chrono::Duration::zero());
+        assert!(intent.is_capture_deadline_near_at(chrono::Duration::zero(), later));
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_latest_payment() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payments/pay_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payment_json()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let intents = mock_client(&server).payment_intents();
+
+        let mut intent = payment_intent("pi_123", 10000);
+        assert!(
+            intents
+                .retrieve_latest_payment(&intent)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        intent.latest_payment = Some("pay_123".to_string());
+        let payment = intents
+            .retrieve_latest_payment(&intent)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payment.id.as_str(), "pay_123");
+        assert_eq!(payment.payment_intent_id, intent.id);
+    }
+
+    #[test]
+    fn test_payment_intent_formatted_amount() {
+        assert_eq!(
+            payment_intent("pi_123", 12050).formatted_amount(),
+            "₱120.50"
+        );
+        assert_eq!(payment_intent("pi_123", 2000).formatted_amount(), "₱20.00");
+    }
+
+    #[test]
+    fn test_matches_checkout() {
+        let intent = payment_intent("pi_123", 10000);
+        let mut session = checkout_session(10000, false);
+        assert!(intent.matches_checkout(&session));
+
+        session.payment_intent = Some(intent.clone());
+        assert_eq!(session.payment_intent_id(), Some(&intent.id));
+        assert!(intent.matches_checkout(&session));
+    }
+
+    #[test]
+    fn test_matches_checkout_mismatch() {
+        let intent = payment_intent("pi_123", 10000);
+
+        assert!(!intent.matches_checkout(&checkout_session(5000, false)));
+        assert!(!intent.matches_checkout(&checkout_session(10000, true)));
+
+        let mut session = checkout_session(10000, false);
+        session.amount = None;
+        assert!(!intent.matches_checkout(&session));
+
+        let mut session = checkout_session(10000, false);
+        session.payment_intent = Some(payment_intent("pi_other", 10000));
+        assert!(!intent.matches_checkout(&session));
+    }
+
+    #[test]
+    fn test_optional_payment_intent_round_trip() {
+        let intent = OptionalPaymentIntent {
+            id: PaymentIntentId::new("pi_123"),
+            amount: Some(10000),
+            amount_received: None,
+            amount_capturable: None,
+            client_secret: None,
+            currency: Some(Currency::PHP),
+            description: None,
+            livemode: Some(false),
+            metadata: None,
+            latest_payment: None,
+            last_payment_error: None,
+            payment_method_id: None,
+            payment_methods: None,
+            payment_method_options: None,
+            statement_descriptor: None,
+            status: Some(PaymentIntentStatus::AwaitingPaymentMethod),
+            next_action: None,
+            return_url: None,
+            capture_before_at: None,
+            confirmation_method: None,
+            created_at: None,
+            updated_at: None,
+        };
+
+        assert_round_trip(&intent);
+    }
 }
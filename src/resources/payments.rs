@@ -3,10 +3,17 @@
 //! Payments represent successful payment transactions.
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
-    resources::customers::Customer,
-    types::{Currency, Metadata, PaymentId, PaymentIntentId, PaymentMethod, Timestamp},
+    resources::{
+        customers::Customer,
+        paths,
+        refunds::{Refund, RefundListParams, RefundStatus, Refunds},
+    },
+    types::{
+        Currency, FeeSchedule, List, ListParams, Metadata, PaymentId, PaymentIntentId,
+        PaymentMethod, RefundId, Timestamp,
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -29,7 +36,76 @@ impl Payments {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/payments/retrieve)
     pub async fn retrieve(&self, id: &PaymentId) -> Result<Payment> {
-        self.http.get(&format!("/payments/{}", id.as_str())).await
+        self.http.get(&paths::resource(id)).await
+    }
+
+    /// Retrieve a Payment resource by ID with its refunds embedded in [`Payment::refunds`].
+    ///
+    /// Endpoint: `GET /payments/:id?expand[]=refunds`
+    pub async fn retrieve_with_refunds(&self, id: &PaymentId) -> Result<Payment> {
+        self.http
+            .get_expanded(&paths::resource(id), &["refunds"])
+            .await
+    }
+
+    /// List Payment resources, optionally filtered by metadata.
+    ///
+    /// PayRex does not expose a dedicated search endpoint for payments, so lookups such as "the
+    /// payment for order `12345`" are done by filtering the list on metadata.
+    ///
+    /// Endpoint: `GET /payments`
+    pub async fn list(&self, params: Option<PaymentListParams>) -> Result<List<Payment>> {
+        if let Some(params) = &params {
+            params.list_params.validate()?;
+        }
+        self.http
+            .get_with_params(paths::collection::<PaymentId>(), &params)
+            .await
+    }
+
+    /// Retrieves a payment together with all of its refunds and their totals.
+    ///
+    /// Follows every page of [`Refunds::list_stream`](crate::resources::Refunds::list_stream)
+    /// for the payment. If the succeeded refunds do not add up to the payment's
+    /// `amount_refunded`, e.g. because a refund succeeded between the two calls, the summary is
+    /// returned with [`RefundSummary::consistent`] unset; retrying usually resolves it.
+    pub async fn refund_summary(&self, payment_id: &PaymentId) -> Result<RefundSummary> {
+        let payment = self.retrieve(payment_id).await?;
+
+        let mut refunds: Vec<Refund> = Vec::new();
+        let mut stream = Refunds::new(Arc::clone(&self.http))
+            .list_stream(RefundListParams::new().payment_id(payment_id.clone()));
+        while let Some(refund) = stream.next().await {
+            refunds.push(refund?);
+        }
+
+        let sum = |status: RefundStatus| -> i64 {
+            refunds
+                .iter()
+                .filter(|refund| refund.status == status)
+                .map(|refund| refund.amount)
+                .sum()
+        };
+        let total_refunded = sum(RefundStatus::Succeeded);
+        let total_pending = sum(RefundStatus::Pending);
+
+        let amount = i64::try_from(payment.amount).unwrap_or(i64::MAX);
+        Ok(RefundSummary {
+            consistent: u64::try_from(total_refunded).ok() == Some(payment.amount_refunded),
+            refunds,
+            total_refunded,
+            refundable_remaining: (amount - total_refunded - total_pending).max(0),
+        })
+    }
+
+    /// Estimates the PayRex fee for charging `amount` with `method`, using the client's
+    /// [`FeeSchedule`](crate::ConfigBuilder::fee_schedule).
+    ///
+    /// Returns `None` when the schedule has no rate for `method`. This is a local calculation;
+    /// no request is sent.
+    #[must_use]
+    pub fn estimate_fee(&self, amount: i64, method: &PaymentMethod) -> Option<i64> {
+        self.http.config().fee_schedule().estimate(amount, method)
     }
 
     /// Update a Payment resource by ID.
@@ -38,9 +114,7 @@ impl Payments {
     ///
     /// [API Reference](https://docs.payrexhq.com/docs/api/payments/update)
     pub async fn update(&self, id: &PaymentId, params: UpdatePayment) -> Result<Payment> {
-        self.http
-            .patch(&format!("/payments/{}", id.as_str()), &params)
-            .await
+        self.http.patch(&paths::resource(id), &params).await
     }
 }
 
@@ -86,6 +160,7 @@ pub struct Payment {
 
     /// The value is `true` if the resource's mode is live or the value is `false` if the resource is
     /// in test mode.
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
 
     /// A set of key-value pairs attached to the Payment. This is useful for storing additional
@@ -117,13 +192,54 @@ pub struct Payment {
     /// fully refunded while the value is `false` if the payment has no refunds.
     pub refunded: bool,
 
+    /// The refunds of the Payment. Only present when retrieved with
+    /// [`Payments::retrieve_with_refunds`], which expands them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refunds: Option<List<Refund>>,
+
     /// The time the resource was created and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `created`, the field name used by older payloads.
+    #[serde(alias = "created")]
     pub created_at: Timestamp,
 
     /// The time the resource was updated and measured in seconds since the Unix epoch.
+    ///
+    /// Also accepts `updated`, the field name used by older payloads.
+    #[serde(alias = "updated")]
     pub updated_at: Timestamp,
 }
 
+impl Payment {
+    /// Estimates the PayRex fee for charging `amount` with `method` at the standard rates of
+    /// [`FeeSchedule::default`].
+    ///
+    /// Use [`Payments::estimate_fee`] to apply the rates configured on the client instead.
+    #[must_use]
+    pub fn estimate_fee(amount: i64, method: &PaymentMethod) -> Option<i64> {
+        FeeSchedule::default().estimate(amount, method)
+    }
+
+    /// Returns [`Payment::amount`] formatted in the payment's currency, e.g. `₱120.50`.
+    #[must_use]
+    pub fn formatted_amount(&self) -> String {
+        self.currency
+            .format_amount(i64::try_from(self.amount).unwrap_or(i64::MAX))
+    }
+
+    /// Returns [`Payment::net_amount`] formatted in the payment's currency.
+    #[must_use]
+    pub fn formatted_net_amount(&self) -> String {
+        self.currency.format_amount(self.net_amount)
+    }
+
+    /// Returns [`Payment::fee`] formatted in the payment's currency.
+    #[must_use]
+    pub fn formatted_fee(&self) -> String {
+        self.currency.format_amount(self.fee)
+    }
+}
+
 /// Contains the billing information of the customer.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Billing {
@@ -168,7 +284,26 @@ pub struct Address {
     pub country: Option<String>,
 }
 
-/// Type of payment method with additional metadata for cards.
+/// The refund history of a payment, returned by [`Payments::refund_summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefundSummary {
+    /// Every refund of the payment, in the order PayRex listed them.
+    pub refunds: Vec<Refund>,
+
+    /// The sum of the succeeded refunds.
+    pub total_refunded: i64,
+
+    /// How much can still be refunded: the payment amount minus succeeded and pending refunds.
+    pub refundable_remaining: i64,
+
+    /// Whether `total_refunded` matches the payment's `amount_refunded`.
+    ///
+    /// `false` means a refund changed while the summary was being built; fetch it again before
+    /// relying on the totals.
+    pub consistent: bool,
+}
+
+/// Type of payment method with additional metadata for the method used.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PaymentMethodTypes {
     /// Defines the payment method of the Payment.
@@ -177,6 +312,30 @@ pub struct PaymentMethodTypes {
 
     /// Additional metadata included if the `type` is card.
     pub card: Option<PaymentMethodTypesCard>,
+
+    /// Additional metadata included if the `type` is gcash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gcash: Option<PaymentMethodTypesDetails>,
+
+    /// Additional metadata included if the `type` is maya.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maya: Option<PaymentMethodTypesDetails>,
+
+    /// Additional metadata included if the `type` is qrph.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qrph: Option<PaymentMethodTypesDetails>,
+}
+
+impl PaymentMethodTypes {
+    /// Returns the reference number of a gcash, maya, or qrph payment, e.g. to print on a
+    /// receipt.
+    #[must_use]
+    pub fn reference_number(&self) -> Option<&str> {
+        [&self.gcash, &self.maya, &self.qrph]
+            .into_iter()
+            .flatten()
+            .find_map(|details| details.reference_number.as_deref())
+    }
 }
 
 /// This is only visible if the `payment_method.type` is card.
@@ -192,6 +351,21 @@ pub struct PaymentMethodTypesCard {
     pub brand: String,
 }
 
+/// This is only visible if the `payment_method.type` is gcash, maya, or qrph.
+///
+/// PayRex returns different details per method, so only the reference number is typed and the
+/// rest is kept in [`PaymentMethodTypesDetails::other`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentMethodTypesDetails {
+    /// The reference number of the transaction with the wallet or bank.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_number: Option<String>,
+
+    /// Any other details returned for the payment method.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
 /// Represents the status of a payment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -219,6 +393,52 @@ pub struct UpdatePayment {
     pub metadata: Option<Metadata>,
 }
 
+/// Query parameters when listing payments.
+///
+/// Metadata filters are encoded as `metadata[key]=value`, so callers build them from a
+/// [`Metadata`] value instead of concatenating query strings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentListParams {
+    /// Pagination parameters.
+    #[serde(flatten)]
+    pub list_params: ListParams,
+
+    /// Only return payments whose metadata contains all of these key-value pairs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+}
+
+impl PaymentListParams {
+    /// Creates a new [`PaymentListParams`] instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the pagination parameters.
+    #[must_use]
+    pub fn list_params(mut self, list_params: ListParams) -> Self {
+        self.list_params = list_params;
+        self
+    }
+
+    /// Replaces the metadata filter.
+    #[must_use]
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
+        self
+    }
+
+    /// Adds a single `metadata[key] = value` filter, keeping any filters already set.
+    #[must_use]
+    pub fn metadata_eq(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .get_or_insert_with(Metadata::new)
+            .insert(key, value);
+        self
+    }
+}
+
 impl UpdatePayment {
     /// Creates a new [`UpdatePayment`] instance.
     pub fn new() -> Self {
@@ -232,8 +452,8 @@ impl UpdatePayment {
     }
 
     /// Sets the metadata in the query params for updating a payment.
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 }
@@ -241,6 +461,7 @@ impl UpdatePayment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client, mock_config, payment_json};
 
     #[test]
     fn test_update_payment_builder() {
@@ -255,6 +476,21 @@ mod tests {
         assert_eq!(params.metadata, Some(metadata));
     }
 
+    #[test]
+    fn test_payment_list_params_serialization() {
+        let params = PaymentListParams::new()
+            .list_params(ListParams::new().limit(10))
+            .metadata_eq("order_id", "12345");
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["limit"], 10);
+        assert_eq!(json["metadata"]["order_id"], "12345");
+        assert!(json.get("after").is_none());
+
+        let empty = serde_json::to_string(&PaymentListParams::new()).unwrap();
+        assert_eq!(empty, "{}");
+    }
+
     #[test]
     fn test_payment_status_serialization() {
         let status = PaymentStatus::Paid;
@@ -277,6 +513,8 @@ mod tests {
             country: Some("PH".to_string()),
         };
 
+        assert_round_trip(&address);
+
         let serialized = serde_json::to_string(&address).unwrap();
         let expected = r#"{"line1":"BGC","line2":"Apt 4B","city":"Taguig","state":"NCR","postal_code":"1635","country":"PH"}"#;
         assert_eq!(serialized, expected);
@@ -300,6 +538,8 @@ mod tests {
             address,
         };
 
+        assert_round_trip(&billing);
+
         let serialized = serde_json::to_string(&billing).unwrap();
         let expected = r#"{"name":"John Doe","email":"johndoe@gmail.com","phone":"1234567890","address":{"line1":"BGC","line2":"Apt 4B","city":"Taguig","state":"NCR","postal_code":"1635","country":"PH"}}"#;
 
@@ -315,12 +555,294 @@ mod tests {
                 last4: "2710".to_string(),
                 brand: "MasterCard".to_string(),
             }),
+            gcash: None,
+            maya: None,
+            qrph: None,
         };
 
+        assert_round_trip(&payment_method);
+
         let serialized = serde_json::to_string(&payment_method).unwrap();
         let expected =
             r#"{"type":"card","card":{"first6":"511263","last4":"2710","brand":"MasterCard"}}"#;
 
         assert_eq!(serialized, expected);
     }
+
+    #[test]
+    fn test_payment_method_types_gcash_deserialization() {
+        let payment_method: PaymentMethodTypes = serde_json::from_value(serde_json::json!({
+            "type": "gcash",
+            "card": null,
+            "gcash": { "reference_number": "9012345678901", "account_name": "J*** D." },
+        }))
+        .unwrap();
+
+        assert_eq!(payment_method.method_type, PaymentMethod::GCash);
+        assert_eq!(payment_method.reference_number(), Some("9012345678901"));
+        let gcash = payment_method.gcash.as_ref().unwrap();
+        assert_eq!(gcash.other["account_name"], "J*** D.");
+        assert_round_trip(&payment_method);
+
+        let unknown: PaymentMethodTypes = serde_json::from_value(serde_json::json!({
+            "type": "billease",
+            "billease": { "installments": 3 },
+        }))
+        .unwrap();
+        assert_eq!(
+            unknown.method_type,
+            PaymentMethod::Unknown("billease".to_string())
+        );
+        assert_eq!(unknown.reference_number(), None);
+    }
+
+    #[test]
+    fn test_payment_formatted_amounts() {
+        let mut json = payment_json();
+        json["amount"] = 12050.into();
+        json["net_amount"] = 11700.into();
+        let payment: Payment = serde_json::from_value(json).unwrap();
+
+        assert_eq!(payment.formatted_amount(), "₱120.50");
+        assert_eq!(payment.formatted_net_amount(), "₱117.00");
+        assert_eq!(payment.formatted_fee(), "₱3.50");
+    }
+
+    async fn refund_summary_against(amount_refunded: u64) -> Result<RefundSummary> {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let refund = |id: &str, amount: i64, status: &str| {
+            serde_json::json!({
+                "id": id,
+                "amount": amount,
+                "currency": "PHP",
+                "livemode": false,
+                "status": status,
+                "reason": "requested_by_customer",
+                "payment_id": "pay_123",
+                "created_at": 1_700_000_000,
+                "updated_at": 1_700_000_000,
+            })
+        };
+
+        let mut payment = payment_json();
+        payment["amount_refunded"] = amount_refunded.into();
+        payment["refunded"] = true.into();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payments/pay_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payment))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/refunds"))
+            .and(body_string_contains("payment_id=pay_123"))
+            .and(body_string_contains("after=re_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [refund("re_2", 1000, "succeeded"), refund("re_3", 500, "pending")],
+                "has_more": false,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/refunds"))
+            .and(body_string_contains("payment_id=pay_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [refund("re_1", 2000, "succeeded")],
+                "has_more": true,
+            })))
+            .mount(&server)
+            .await;
+
+        mock_client(&server)
+            .payments()
+            .refund_summary(&PaymentId::new("pay_123"))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_refund_summary() {
+        let summary = refund_summary_against(3000).await.unwrap();
+
+        let ids: Vec<_> = summary.refunds.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["re_1", "re_2", "re_3"]);
+        assert_eq!(summary.total_refunded, 3000);
+        assert_eq!(summary.refundable_remaining, 6500);
+        assert!(summary.consistent);
+    }
+
+    #[tokio::test]
+    async fn test_refund_summary_flags_inconsistent_totals() {
+        let summary = refund_summary_against(2000).await.unwrap();
+        assert_eq!(summary.total_refunded, 3000);
+        assert!(!summary.consistent);
+    }
+
+    #[test]
+    fn test_estimate_fee() {
+        assert_eq!(
+            Payment::estimate_fee(100_000, &PaymentMethod::Card),
+            Some(5_000)
+        );
+        assert_eq!(
+            Payment::estimate_fee(100_000, &PaymentMethod::GCash),
+            Some(2_500)
+        );
+
+        let client = crate::Client::builder()
+            .api_key("sk_test_123")
+            .fee_schedule(
+                FeeSchedule::default()
+                    .with_rate(PaymentMethod::Card, crate::types::FeeRate::new(300, 1_000)),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            client
+                .payments()
+                .estimate_fee(100_000, &PaymentMethod::Card),
+            Some(4_000)
+        );
+        assert_eq!(
+            client
+                .payments()
+                .estimate_fee(100_000, &PaymentMethod::GCash),
+            Some(2_500)
+        );
+    }
+
+    #[test]
+    fn test_payment_accepts_legacy_field_names() {
+        let mut json = payment_json();
+        let object = json.as_object_mut().unwrap();
+        let created_at = object.remove("created_at").unwrap();
+        let updated_at = object.remove("updated_at").unwrap();
+        object.insert("created".to_string(), created_at);
+        object.insert("updated".to_string(), updated_at);
+        let payment: Payment = serde_json::from_value(json).unwrap();
+
+        assert_eq!(payment.created_at, Timestamp::from_unix(1_700_000_000));
+        assert_eq!(payment.updated_at, Timestamp::from_unix(1_700_000_100));
+    }
+
+    #[test]
+    fn test_payment_round_trip() {
+        let mut metadata = Metadata::new();
+        metadata.insert("order_id", "12345");
+
+        let payment = Payment {
+            id: PaymentId::new("pay_123"),
+            amount: 10000,
+            amount_refunded: 2500,
+            billing: None,
+            currency: Currency::PHP,
+            description: Some("Order #12345".to_string()),
+            fee: 250,
+            livemode: false,
+            metadata: Some(metadata),
+            net_amount: 9750,
+            payment_intent_id: PaymentIntentId::new("pi_123"),
+            status: PaymentStatus::Paid,
+            customer: None,
+            payment_method: PaymentMethodTypes {
+                method_type: PaymentMethod::GCash,
+                card: None,
+                gcash: Some(PaymentMethodTypesDetails {
+                    reference_number: Some("9012345678901".to_string()),
+                    ..Default::default()
+                }),
+                maya: None,
+                qrph: None,
+            },
+            refunded: true,
+            refunds: None,
+            created_at: Timestamp::from_unix(1_700_000_000),
+            updated_at: Timestamp::from_unix(1_700_000_100),
+        };
+
+        assert_round_trip(&payment);
+    }
+
+    #[test]
+    fn test_payment_embedded_refunds_deserialization() {
+        let mut payload = payment_json();
+        payload["amount_refunded"] = 2500.into();
+        payload["refunded"] = true.into();
+
+        let payment: Payment = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(payment.refunds, None);
+
+        payload["refunds"] = serde_json::json!({
+            "data": [{
+                "id": "re_123",
+                "amount": 2500,
+                "currency": "PHP",
+                "livemode": false,
+                "status": "succeeded",
+                "reason": "requested_by_customer",
+                "payment_id": "pay_123",
+                "created_at": 1_700_000_000,
+                "updated_at": 1_700_000_000,
+            }],
+            "has_more": false,
+        });
+        let payment: Payment = serde_json::from_value(payload).unwrap();
+        let refunds = payment.refunds.as_ref().unwrap();
+        assert_eq!(refunds.data.len(), 1);
+        assert_eq!(refunds.data[0].id.as_str(), "re_123");
+        assert_round_trip(&payment);
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_with_refunds_expands_refunds() {
+        use wiremock::matchers::{body_string, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mut payment = payment_json();
+        payment["refunds"] = serde_json::json!({ "data": [], "has_more": false });
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payments/pay_123"))
+            .and(body_string("expand%5B%5D=refunds"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payment))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let payment = mock_client(&server)
+            .payments()
+            .retrieve_with_refunds(&PaymentId::new("pay_123"))
+            .await
+            .unwrap();
+        assert!(payment.refunds.unwrap().data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_with_refunds_expands_refunds_as_json() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payments/pay_123"))
+            .and(body_json(serde_json::json!({ "expand": ["refunds"] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payment_json()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = mock_config(&server)
+            .body_encoding(crate::BodyEncoding::Json)
+            .build()
+            .unwrap();
+        crate::Client::with_config(config)
+            .unwrap()
+            .payments()
+            .retrieve_with_refunds(&PaymentId::new("pay_123"))
+            .await
+            .unwrap();
+    }
 }
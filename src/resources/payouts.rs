@@ -5,7 +5,11 @@
 use crate::{
     Result,
     http::HttpClient,
-    types::{List, ListParams, PayoutId, PayoutTransactionId, Timestamp},
+    resources::paths,
+    types::{
+        List, ListParams, ListStream, Paginator, PayoutId, PayoutTransactionId, RangeQuery,
+        Resource, Timestamp,
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -21,15 +25,64 @@ impl Payouts {
         Self { http }
     }
 
+    /// List payouts, optionally filtered by status, creation date or expected arrival date.
+    ///
+    /// Endpoint: `GET /payouts`
+    pub async fn list(&self, params: Option<PayoutListParams>) -> Result<List<Payout>> {
+        if let Some(params) = &params {
+            params.list_params.validate()?;
+        }
+        self.http
+            .get_with_params(paths::collection::<PayoutId>(), &params)
+            .await
+    }
+
     pub async fn list_transactions(
         &self,
         id: &PayoutId,
         params: Option<ListParams>,
     ) -> Result<List<PayoutTransaction>> {
+        if let Some(params) = &params {
+            params.validate()?;
+        }
         self.http
-            .get_with_params(&format!("/payouts/{}/transactions", id.as_str()), &params)
+            .get_with_params(&paths::action(id, "transactions"), &params)
             .await
     }
+
+    /// Streams every transaction of a payout, one page at a time.
+    ///
+    /// Unlike [`Payouts::list_transactions`], this follows `has_more` across pages while holding
+    /// only the current page in memory, which keeps memory flat when reconciling payouts with
+    /// tens of thousands of transactions. Use `params` to set the page size.
+    #[must_use]
+    pub fn transactions_stream(
+        &self,
+        id: &PayoutId,
+        params: Option<ListParams>,
+    ) -> ListStream<PayoutTransaction> {
+        ListStream::new(
+            Arc::clone(&self.http),
+            paths::action(id, "transactions"),
+            params.unwrap_or_default(),
+        )
+    }
+
+    /// Pages through the transactions of a payout in either direction.
+    ///
+    /// Endpoint: `GET /payouts/:id/transactions`
+    #[must_use]
+    pub fn transactions_paginator(
+        &self,
+        id: &PayoutId,
+        params: Option<ListParams>,
+    ) -> Paginator<PayoutTransaction> {
+        Paginator::new(
+            Arc::clone(&self.http),
+            paths::action(id, "transactions"),
+            params.unwrap_or_default(),
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -38,14 +91,35 @@ pub struct Payout {
     pub amount: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub destination: Option<PayoutDestination>,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub net_amount: Option<i64>,
     pub status: PayoutStatus,
+    /// When the payout is expected to arrive in the destination account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arrival_at: Option<Timestamp>,
+    /// The text that appears on the destination account's bank statement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement_descriptor: Option<String>,
     pub created_at: Timestamp,
     pub updated_at: Option<Timestamp>,
 }
 
+impl Payout {
+    /// Returns `true` if the payout has been deposited to the destination account.
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.status == PayoutStatus::Successful
+    }
+
+    /// Returns `true` if the payout's status will no longer change.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_terminal()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PayoutStatus {
@@ -53,6 +127,73 @@ pub enum PayoutStatus {
     InTransit,
     Failed,
     Cancelled,
+    /// The payout was deposited to the destination account.
+    #[serde(alias = "paid", alias = "deposited")]
+    Successful,
+    /// A status this version of the SDK does not know about.
+    #[serde(other)]
+    Unknown,
+}
+
+impl PayoutStatus {
+    /// Returns `true` for statuses a payout cannot move out of.
+    #[must_use]
+    pub const fn is_terminal(self) -> bool {
+        matches!(self, Self::Successful | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Query parameters when listing payouts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PayoutListParams {
+    /// Pagination parameters.
+    #[serde(flatten)]
+    pub list_params: ListParams,
+    /// Only return payouts with this status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PayoutStatus>,
+    /// Only return payouts created within this range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<RangeQuery<Timestamp>>,
+    /// Only return payouts expected to arrive within this range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arrival_at: Option<RangeQuery<Timestamp>>,
+}
+
+impl PayoutListParams {
+    /// Creates a new [`PayoutListParams`] instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the pagination parameters.
+    #[must_use]
+    pub fn list_params(mut self, list_params: ListParams) -> Self {
+        self.list_params = list_params;
+        self
+    }
+
+    /// Filters payouts by status.
+    #[must_use]
+    pub const fn status(mut self, status: PayoutStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Filters payouts by creation date.
+    #[must_use]
+    pub fn created_at(mut self, range: RangeQuery<Timestamp>) -> Self {
+        self.created_at = Some(range);
+        self
+    }
+
+    /// Filters payouts by expected arrival date.
+    #[must_use]
+    pub fn arrival_at(mut self, range: RangeQuery<Timestamp>) -> Self {
+        self.arrival_at = Some(range);
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -82,11 +223,183 @@ pub struct PayoutTransaction {
     pub updated_at: Option<Timestamp>,
 }
 
+impl Resource for PayoutTransaction {
+    type Id = PayoutTransactionId;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn object_type() -> &'static str {
+        "payout_transaction"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::{PayoutId, PayoutTransactionId, Timestamp};
     use serde_json;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn transaction_json(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "amount": 500,
+            "net_amount": 490,
+            "transaction_id": "pot_src",
+            "transaction_type": "payment",
+            "created_at": 1_610_002_000,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_transactions_stream_holds_one_page_at_a_time() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payouts/po_123/transactions"))
+            .and(body_string_contains("after=pot_2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [transaction_json("pot_3")],
+                "has_more": false,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/payouts/po_123/transactions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [transaction_json("pot_1"), transaction_json("pot_2")],
+                "has_more": true,
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let mut stream = mock_client(&server)
+            .payouts()
+            .transactions_stream(&PayoutId::new("po_123"), Some(ListParams::new().limit(2)));
+
+        let mut ids = Vec::new();
+        let mut max_buffered = 0;
+        while let Some(tx) = stream.next().await {
+            ids.push(tx.unwrap().id.as_str().to_string());
+            max_buffered = max_buffered.max(stream.buffered());
+        }
+
+        assert_eq!(ids, vec!["pot_1", "pot_2", "pot_3"]);
+        assert!(max_buffered < 2);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_stream_sends_one_cursor() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(PagedTransactions)
+            .mount(&server)
+            .await;
+        let client = mock_client(&server);
+        let id = PayoutId::new("po_123");
+
+        let mut stream = client
+            .payouts()
+            .transactions_stream(&id, Some(ListParams::new().after("pot_1")));
+        let mut ids = Vec::new();
+        while let Some(tx) = stream.next().await {
+            ids.push(tx.unwrap().id.as_str().to_string());
+        }
+        assert_eq!(ids, ["pot_2", "pot_3", "pot_4", "pot_5"]);
+
+        let bodies: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| String::from_utf8_lossy(&r.body).to_string())
+            .collect();
+        assert_eq!(bodies, ["after=pot_1", "after=pot_3"]);
+
+        for params in [
+            ListParams::new().before("pot_5"),
+            ListParams::new().after("pot_1").before("pot_5"),
+        ] {
+            let mut stream = client.payouts().transactions_stream(&id, Some(params));
+            assert!(matches!(
+                stream.next().await,
+                Some(Err(crate::Error::InvalidRequest(_)))
+            ));
+            assert!(stream.next().await.is_none());
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    /// Serves five transactions two at a time, honouring `after` and `before` cursors.
+    struct PagedTransactions;
+
+    impl wiremock::Respond for PagedTransactions {
+        fn respond(&self, request: &wiremock::Request) -> ResponseTemplate {
+            let ids = ["pot_1", "pot_2", "pot_3", "pot_4", "pot_5"];
+            let body = String::from_utf8_lossy(&request.body).to_string();
+            let cursor = |name: &str| {
+                body.split('&')
+                    .find_map(|pair| pair.strip_prefix(&format!("{name}=")))
+                    .and_then(|id| ids.iter().position(|candidate| *candidate == id))
+            };
+
+            let (page, has_more) = if let Some(index) = cursor("after") {
+                let rest = &ids[index + 1..];
+                (&rest[..rest.len().min(2)], rest.len() > 2)
+            } else if let Some(index) = cursor("before") {
+                let rest = &ids[..index];
+                (&rest[rest.len().saturating_sub(2)..], rest.len() > 2)
+            } else {
+                (&ids[..2], true)
+            };
+
+            let data: Vec<_> = page.iter().map(|id| transaction_json(id)).collect();
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "data": data, "has_more": has_more }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transactions_paginator_navigates_both_ways() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/payouts/po_123/transactions"))
+            .respond_with(PagedTransactions)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        let mut pages = client
+            .payouts()
+            .transactions_paginator(&PayoutId::new("po_123"), Some(ListParams::new().limit(2)));
+
+        fn ids(page: Option<&List<PayoutTransaction>>) -> Vec<String> {
+            page.unwrap()
+                .iter()
+                .map(|tx| tx.id.as_str().to_string())
+                .collect()
+        }
+
+        assert!(pages.prev_page().await.unwrap().is_none());
+        assert_eq!(ids(pages.next_page().await.unwrap()), ["pot_1", "pot_2"]);
+        assert!(pages.prev_page().await.unwrap().is_none());
+        assert_eq!(ids(pages.next_page().await.unwrap()), ["pot_3", "pot_4"]);
+        assert_eq!(ids(pages.next_page().await.unwrap()), ["pot_5"]);
+        assert!(pages.next_page().await.unwrap().is_none());
+        assert_eq!(ids(pages.current_page()), ["pot_5"]);
+
+        assert_eq!(ids(pages.prev_page().await.unwrap()), ["pot_3", "pot_4"]);
+        assert_eq!(ids(pages.prev_page().await.unwrap()), ["pot_1", "pot_2"]);
+        assert!(pages.prev_page().await.unwrap().is_none());
+        assert_eq!(ids(pages.next_page().await.unwrap()), ["pot_3", "pot_4"]);
+
+        assert_eq!(server.received_requests().await.unwrap().len(), 6);
+    }
 
     #[test]
     fn test_payout_status_serialization() {
@@ -107,6 +420,100 @@ mod tests {
         assert_eq!(json, "\"cancelled\"");
     }
 
+    #[test]
+    fn test_payout_status_deposited_deserialization() {
+        for status in ["\"successful\"", "\"deposited\"", "\"paid\""] {
+            let status: PayoutStatus = serde_json::from_str(status).unwrap();
+            assert_eq!(status, PayoutStatus::Successful);
+        }
+
+        let status: PayoutStatus = serde_json::from_str("\"on_hold\"").unwrap();
+        assert_eq!(status, PayoutStatus::Unknown);
+    }
+
+    #[test]
+    fn test_payout_arrival_at_deserialization() {
+        let payout: Payout = serde_json::from_value(serde_json::json!({
+            "id": "po_123",
+            "amount": 5000,
+            "livemode": false,
+            "status": "in_transit",
+            "arrival_at": 1_610_172_800,
+            "created_at": 1_610_000_000,
+            "updated_at": null,
+        }))
+        .unwrap();
+
+        assert_eq!(payout.arrival_at, Some(Timestamp::from_unix(1_610_172_800)));
+        assert_eq!(payout.statement_descriptor, None);
+    }
+
+    #[test]
+    fn test_payout_list_params_serialization() {
+        let params = PayoutListParams::new()
+            .list_params(ListParams::new().limit(10))
+            .arrival_at(
+                RangeQuery::new()
+                    .gte(Timestamp::from_unix(1_610_000_000))
+                    .lt(Timestamp::from_unix(1_610_086_400)),
+            );
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["limit"], 10);
+        assert_eq!(json["arrival_at"]["gte"], 1_610_000_000);
+        assert_eq!(json["arrival_at"]["lt"], 1_610_086_400);
+        assert!(json["arrival_at"].get("gt").is_none());
+
+        let query = serde_qs::to_string(&params).unwrap();
+        assert!(query.contains("arrival_at[gte]=1610000000"));
+        assert!(query.contains("arrival_at[lt]=1610086400"));
+    }
+
+    #[test]
+    fn test_payout_list_params_status_and_created_at() {
+        let params = PayoutListParams::new()
+            .status(PayoutStatus::Failed)
+            .created_at(RangeQuery::new().gte(Timestamp::from_unix(1_610_000_000)));
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["created_at"]["gte"], 1_610_000_000);
+        assert!(json.get("arrival_at").is_none());
+
+        let query = serde_qs::to_string(&params).unwrap();
+        assert!(query.contains("status=failed"));
+        assert!(query.contains("created_at[gte]=1610000000"));
+
+        let empty = serde_json::to_value(PayoutListParams::new()).unwrap();
+        assert_eq!(empty, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_payout_settlement_helpers() {
+        let mut payout = Payout {
+            id: PayoutId::new("po_123"),
+            amount: 5000,
+            destination: None,
+            livemode: false,
+            net_amount: None,
+            status: PayoutStatus::InTransit,
+            arrival_at: None,
+            statement_descriptor: None,
+            created_at: Timestamp::from_unix(1_610_000_000),
+            updated_at: None,
+        };
+        assert!(!payout.is_settled());
+        assert!(!payout.is_terminal());
+
+        payout.status = PayoutStatus::Successful;
+        assert!(payout.is_settled());
+        assert!(payout.is_terminal());
+
+        payout.status = PayoutStatus::Failed;
+        assert!(!payout.is_settled());
+        assert!(payout.is_terminal());
+    }
+
     #[test]
     fn test_payout_transaction_type_serialization() {
         let kind = PayoutTransactionType::Payment;
@@ -131,9 +538,13 @@ mod tests {
             livemode: true,
             net_amount: Some(4900),
             status: PayoutStatus::Pending,
+            arrival_at: Some(Timestamp::from_unix(1_610_172_800)),
+            statement_descriptor: Some("PAYREX".to_string()),
             created_at: Timestamp::from_unix(1_610_000_000),
             updated_at: Some(Timestamp::from_unix(1_610_001_000)),
         };
+        assert_round_trip(&payout);
+
         let json = serde_json::to_value(&payout).unwrap();
         assert_eq!(json["id"], "po_123");
         assert_eq!(json["amount"], 5000);
@@ -158,6 +569,8 @@ mod tests {
             created_at: Timestamp::from_unix(1_610_002_000),
             updated_at: None,
         };
+        assert_round_trip(&tx);
+
         let json = serde_json::to_value(&tx).unwrap();
         assert_eq!(json["id"], "pot_abc");
         assert_eq!(json["amount"], 500);
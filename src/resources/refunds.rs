@@ -3,11 +3,17 @@
 //! Refunds allow you to return money to a customer.
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
-    types::{Currency, Metadata, PaymentId, RefundId, Timestamp},
+    resources::paths,
+    types::{
+        Currency, List, ListParams, ListQuery, ListStream, Metadata, PaymentId, RefundId, Resource,
+        Timestamp,
+    },
 };
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -21,15 +27,42 @@ impl Refunds {
         Self { http }
     }
 
-    pub async fn create(&self, params: CreateRefund) -> Result<Refund> {
-        self.http.post("/refunds", &params).await
+    pub async fn create(&self, mut params: CreateRefund) -> Result<Refund> {
+        params.validate()?;
+        self.http.apply_default_metadata(&mut params.metadata);
+        self.http
+            .post(paths::collection::<RefundId>(), &params)
+            .await
     }
 
     pub async fn update(&self, id: &RefundId, params: UpdateRefund) -> Result<Refund> {
+        self.http.put(&paths::resource(id), &params).await
+    }
+
+    /// Lists refunds, optionally only those of one payment.
+    ///
+    /// Endpoint: `GET /refunds`
+    pub async fn list(&self, params: Option<RefundListParams>) -> Result<List<Refund>> {
+        if let Some(params) = &params {
+            params.list_params.validate()?;
+        }
         self.http
-            .put(&format!("/refunds/{}", id.as_str()), &params)
+            .get_with_params(paths::collection::<RefundId>(), &params)
             .await
     }
+
+    /// Streams every refund matching `params`, one page at a time.
+    ///
+    /// Unlike [`Refunds::list`], this follows `has_more` across pages while keeping the
+    /// `payment_id` filter on every request.
+    #[must_use]
+    pub fn list_stream(&self, params: RefundListParams) -> ListStream<Refund, RefundListParams> {
+        ListStream::new(
+            Arc::clone(&self.http),
+            paths::collection::<RefundId>(),
+            params,
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -37,6 +70,7 @@ pub struct Refund {
     pub id: RefundId,
     pub amount: i64,
     pub currency: Currency,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     pub status: RefundStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,10 +81,33 @@ pub struct Refund {
     pub payment_id: PaymentId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
+    /// When the refunded amount is expected to be available to the customer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_available_at: Option<Timestamp>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
 
+impl Resource for Refund {
+    type Id = RefundId;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn object_type() -> &'static str {
+        "refund"
+    }
+}
+
+impl Refund {
+    /// Returns `true` if the refund has been sent to the customer.
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.status == RefundStatus::Succeeded
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RefundStatus {
@@ -72,6 +129,76 @@ pub enum RefundReason {
     Others,
 }
 
+impl RefundReason {
+    /// Every refund reason, in the order PayRex lists them.
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::Fraudulent,
+            Self::RequestedByCustomer,
+            Self::ProductOutOfStock,
+            Self::ProductWasDamaged,
+            Self::ServiceNotProvided,
+            Self::ServiceMisaligned,
+            Self::WrongProductReceived,
+            Self::Others,
+        ]
+    }
+
+    /// Returns the value the API uses for the reason, e.g. `requested_by_customer`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fraudulent => "fraudulent",
+            Self::RequestedByCustomer => "requested_by_customer",
+            Self::ProductOutOfStock => "product_out_of_stock",
+            Self::ProductWasDamaged => "product_was_damaged",
+            Self::ServiceNotProvided => "service_not_provided",
+            Self::ServiceMisaligned => "service_misaligned",
+            Self::WrongProductReceived => "wrong_product_received",
+            Self::Others => "others",
+        }
+    }
+
+    /// Returns a human-readable label for the reason, e.g. `Requested by customer`.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Fraudulent => "Fraudulent",
+            Self::RequestedByCustomer => "Requested by customer",
+            Self::ProductOutOfStock => "Product out of stock",
+            Self::ProductWasDamaged => "Product was damaged",
+            Self::ServiceNotProvided => "Service not provided",
+            Self::ServiceMisaligned => "Service misaligned",
+            Self::WrongProductReceived => "Wrong product received",
+            Self::Others => "Others",
+        }
+    }
+}
+
+impl fmt::Display for RefundReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RefundReason {
+    type Err = Error;
+
+    /// Parses the API value of a reason, as returned by [`RefundReason::as_str`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|reason| reason.as_str() == s)
+            .ok_or_else(|| Error::invalid_request(format!("unknown refund reason: {s}")))
+    }
+}
+
+/// Parameters when creating a refund.
+///
+/// `payment_id` and `amount` are required. The [`Default`] value leaves them unset and is
+/// rejected by [`CreateRefund::validate`] until they are filled in.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateRefund {
     pub payment_id: PaymentId,
@@ -86,12 +213,63 @@ pub struct CreateRefund {
     pub description: Option<String>,
 }
 
+/// Query parameters when listing refunds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RefundListParams {
+    /// Pagination parameters.
+    #[serde(flatten)]
+    pub list_params: ListParams,
+
+    /// Only return refunds of this payment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_id: Option<PaymentId>,
+}
+
+impl RefundListParams {
+    /// Creates empty list parameters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return refunds of `payment_id`.
+    #[must_use]
+    pub fn payment_id(mut self, payment_id: PaymentId) -> Self {
+        self.payment_id = Some(payment_id);
+        self
+    }
+}
+
+impl ListQuery for RefundListParams {
+    fn list_params(&self) -> &ListParams {
+        &self.list_params
+    }
+
+    fn list_params_mut(&mut self) -> &mut ListParams {
+        &mut self.list_params
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateRefund {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
 }
 
+impl Default for CreateRefund {
+    fn default() -> Self {
+        Self {
+            payment_id: PaymentId::new_unchecked(""),
+            amount: 0,
+            currency: Currency::default(),
+            reason: RefundReason::Others,
+            metadata: None,
+            remarks: None,
+            description: None,
+        }
+    }
+}
+
 impl CreateRefund {
     #[must_use]
     pub fn new(
@@ -111,8 +289,8 @@ impl CreateRefund {
         }
     }
 
-    pub fn metadata(mut self, metadata: Metadata) -> Self {
-        self.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.metadata = Some(metadata.into());
         self
     }
 
@@ -125,14 +303,50 @@ impl CreateRefund {
         self.description = Some(description.into());
         self
     }
+
+    /// Checks that the required fields are set.
+    ///
+    /// Called by [`Refunds::create`] before the request is sent.
+    pub fn validate(&self) -> Result<()> {
+        if self.payment_id.as_str().is_empty() {
+            return Err(Error::invalid_request("payment_id is required"));
+        }
+        if self.amount <= 0 {
+            return Err(Error::invalid_request("amount must be a positive integer"));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::assert_round_trip;
     use crate::types::{Currency, Metadata, PaymentId, RefundId, Timestamp};
     use serde_json;
 
+    #[test]
+    fn test_create_refund_default_is_rejected() {
+        let result = CreateRefund::default().validate();
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("payment_id")));
+
+        let params = CreateRefund {
+            payment_id: PaymentId::new("pay_123"),
+            ..Default::default()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(Error::InvalidRequest(m)) if m.contains("amount")
+        ));
+
+        let params = CreateRefund {
+            payment_id: PaymentId::new("pay_123"),
+            amount: 1000,
+            ..Default::default()
+        };
+        assert!(params.validate().is_ok());
+    }
+
     #[test]
     fn test_refund_status_serialization() {
         assert_eq!(
@@ -169,6 +383,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_refund_expected_available_at_deserialization() {
+        let refund: Refund = serde_json::from_value(serde_json::json!({
+            "id": "re_123",
+            "amount": 1000,
+            "currency": "PHP",
+            "livemode": false,
+            "status": "pending",
+            "reason": "others",
+            "payment_id": "pay_456",
+            "expected_available_at": 1_620_432_000,
+            "created_at": 1_620_000_000,
+            "updated_at": 1_620_001_000,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            refund.expected_available_at,
+            Some(Timestamp::from_unix(1_620_432_000))
+        );
+        assert!(!refund.is_settled());
+
+        let refund = Refund {
+            status: RefundStatus::Succeeded,
+            ..refund
+        };
+        assert!(refund.is_settled());
+    }
+
     #[test]
     fn test_refund_serialization() {
         let mut metadata = Metadata::new();
@@ -185,10 +428,13 @@ mod tests {
             remarks: Some("note".to_string()),
             payment_id: PaymentId::new("pay_456"),
             metadata: Some(metadata.clone()),
+            expected_available_at: None,
             created_at: Timestamp::from_unix(1_620_000_000),
             updated_at: Timestamp::from_unix(1_620_001_000),
         };
 
+        assert_round_trip(&refund);
+
         let json = serde_json::to_value(&refund).unwrap();
 
         assert_eq!(json["id"], "re_123");
@@ -238,4 +484,32 @@ mod tests {
         let serialized = serde_json::to_string(&params).unwrap();
         assert_eq!(serialized, r#"{"metadata":{"foo":"bar"}}"#);
     }
+
+    #[test]
+    fn test_refund_reason_strings() {
+        for &reason in RefundReason::all() {
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::json!(reason.as_str())
+            );
+            assert_eq!(reason.to_string(), reason.as_str());
+            assert_eq!(reason.as_str().parse::<RefundReason>().unwrap(), reason);
+        }
+
+        assert_eq!(RefundReason::all().len(), 8);
+        assert_eq!(RefundReason::Others.as_str(), "others");
+        assert_eq!(RefundReason::Others.label(), "Others");
+        assert_eq!(
+            "others".parse::<RefundReason>().unwrap(),
+            RefundReason::Others
+        );
+        assert_eq!(
+            RefundReason::RequestedByCustomer.label(),
+            "Requested by customer"
+        );
+        assert!(matches!(
+            "Others".parse::<RefundReason>(),
+            Err(Error::InvalidRequest(m)) if m.contains("Others")
+        ));
+    }
 }
@@ -3,13 +3,19 @@
 //! Webhooks allow you to receive real-time notifications about events.
 
 use crate::{
-    Result,
+    Error, Result,
     http::HttpClient,
-    types::{List, ListParams, Timestamp, WebhookId, event::EventType},
+    resources::paths,
+    types::{List, ListParams, Secret, Timestamp, WebhookId, event::EventType},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Manage the webhook endpoints PayRex sends events to.
+///
+/// The PayRex API does not expose webhook delivery attempts, so there is no endpoint here for
+/// inspecting them. Delivery history and response codes are only available in the PayRex
+/// Dashboard.
 #[derive(Clone)]
 pub struct Webhooks {
     http: Arc<HttpClient>,
@@ -22,50 +28,71 @@ impl Webhooks {
     }
 
     pub async fn create(&self, params: CreateWebhook) -> Result<Webhook> {
-        self.http.post("/webhooks", &params).await
+        params.validate()?;
+        self.http
+            .post(paths::collection::<WebhookId>(), &params)
+            .await
     }
 
     pub async fn retrieve(&self, id: &WebhookId) -> Result<Webhook> {
-        self.http.get(&format!("/webhooks/{}", id.as_str())).await
+        self.http.get(&paths::resource(id)).await
     }
 
     pub async fn update(&self, id: &WebhookId, params: UpdateWebhook) -> Result<Webhook> {
-        self.http
-            .put(&format!("/webhooks/{}", id.as_str()), &params)
-            .await
+        self.http.put(&paths::resource(id), &params).await
     }
 
     pub async fn delete(&self, id: &WebhookId) -> Result<()> {
-        self.http
-            .delete(&format!("/webhooks/{}", id.as_str()))
-            .await
+        self.http.delete(&paths::resource(id)).await
     }
 
-    pub async fn list(&self, params: WebhookListParams) -> Result<List<Webhook>> {
-        self.http.get_with_params("/webhooks", &params).await
+    pub async fn list(&self, params: Option<WebhookListParams>) -> Result<List<Webhook>> {
+        if let Some(base) = params.as_ref().and_then(|params| params.base.as_ref()) {
+            base.validate()?;
+        }
+        self.http
+            .get_with_params(paths::collection::<WebhookId>(), &params)
+            .await
     }
 
     pub async fn enable(&self, id: &WebhookId) -> Result<Webhook> {
-        self.http
-            .post(&format!("/webhooks/{}/enable", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "enable"), &()).await
     }
 
     pub async fn disable(&self, id: &WebhookId) -> Result<Webhook> {
-        self.http
-            .post(&format!("/webhooks/{}/disable", id.as_str()), &())
-            .await
+        self.http.post(&paths::action(id, "disable"), &()).await
     }
+
+    /// Asks PayRex to deliver a test event of `event_type` to the webhook, so a handler can be
+    /// checked without triggering the event for real.
+    ///
+    /// Endpoint: `POST /webhooks/:id/send_test_event`
+    pub async fn send_test_event(&self, id: &WebhookId, event_type: EventType) -> Result<()> {
+        let _: serde::de::IgnoredAny = self
+            .http
+            .post(
+                &paths::action(id, "send_test_event"),
+                &SendTestEvent { event_type },
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SendTestEvent {
+    event_type: EventType,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Webhook {
     pub id: WebhookId,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub secret_key: Option<String>,
+    pub secret_key: Option<Secret<String>>,
     pub status: WebhookStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
     pub url: String,
     pub events: Vec<EventType>,
@@ -73,6 +100,24 @@ pub struct Webhook {
     pub updated_at: Timestamp,
 }
 
+impl Webhook {
+    /// Returns `true` if this webhook is subscribed to `event_type`.
+    ///
+    /// This ignores [`Webhook::status`]; a disabled webhook still reports its subscriptions.
+    #[must_use]
+    pub fn subscribes_to(&self, event_type: &EventType) -> bool {
+        self.events.contains(event_type)
+    }
+
+    /// Returns `true` if this webhook is subscribed to any event in the `category`, e.g.
+    /// `"payment_intent"` or `"payment_intent."`.
+    #[must_use]
+    pub fn subscribes_to_category(&self, category: &str) -> bool {
+        let category = category.strip_suffix('.').unwrap_or(category);
+        self.events.iter().any(|event| event.category() == category)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum WebhookStatus {
@@ -80,7 +125,11 @@ pub enum WebhookStatus {
     Disabled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Parameters when creating a webhook.
+///
+/// The [`Default`] value leaves `url` and `events` empty and is rejected by
+/// [`CreateWebhook::validate`] until they are filled in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateWebhook {
     pub url: String,
     pub events: Vec<EventType>,
@@ -123,6 +172,21 @@ impl CreateWebhook {
         self.description = Some(description.into());
         self
     }
+
+    /// Checks that the required fields are set.
+    ///
+    /// Called by [`Webhooks::create`] before the request is sent.
+    pub fn validate(&self) -> Result<()> {
+        if self.url.is_empty() {
+            return Err(Error::invalid_request("url is required"));
+        }
+        if self.events.is_empty() {
+            return Err(Error::invalid_request(
+                "events must contain at least one event type",
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl UpdateWebhook {
@@ -154,9 +218,25 @@ impl UpdateWebhook {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{assert_round_trip, mock_client};
     use crate::types::event::CheckoutSessionEvent;
     use serde_json;
 
+    #[test]
+    fn test_create_webhook_default_is_rejected() {
+        let result = CreateWebhook::default().validate();
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("url")));
+
+        let params = CreateWebhook {
+            url: "https://example.com/webhooks".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            params.validate(),
+            Err(Error::InvalidRequest(m)) if m.contains("events")
+        ));
+    }
+
     #[test]
     fn test_webhook_status_serialization() {
         assert_eq!(
@@ -173,7 +253,7 @@ mod tests {
     fn test_webhook_serialization() {
         let webhook = Webhook {
             id: WebhookId::new("wh_123"),
-            secret_key: Some("secret".to_string()),
+            secret_key: Some(Secret::from("whsk_abc123")),
             status: WebhookStatus::Enabled,
             description: Some("desc".to_string()),
             livemode: false,
@@ -183,9 +263,13 @@ mod tests {
             updated_at: Timestamp::from_unix(1_600_001),
         };
 
+        assert_round_trip(&webhook);
+
+        assert!(!format!("{webhook:?}").contains("whsk_abc123"));
+
         let json = serde_json::to_value(&webhook).unwrap();
         assert_eq!(json["id"], "wh_123");
-        assert_eq!(json["secret_key"], "secret");
+        assert_eq!(json["secret_key"], "whsk_abc123");
         assert_eq!(json["status"], "enabled");
         assert_eq!(json["description"], "desc");
         assert_eq!(json["livemode"], false);
@@ -197,6 +281,37 @@ mod tests {
         assert_eq!(json["updated_at"], 1_600_001);
     }
 
+    #[test]
+    fn test_webhook_subscriptions() {
+        use crate::types::event::{PaymentIntentEvent, RefundEvent};
+
+        let webhook = Webhook {
+            id: WebhookId::new("wh_123"),
+            secret_key: None,
+            status: WebhookStatus::Enabled,
+            description: None,
+            livemode: false,
+            url: "https://example.com/webhooks".to_string(),
+            events: vec![
+                EventType::PaymentIntent(PaymentIntentEvent::Succeeded),
+                EventType::CheckoutSession(CheckoutSessionEvent::Expired),
+            ],
+            created_at: Timestamp::from_unix(1_600_000),
+            updated_at: Timestamp::from_unix(1_600_001),
+        };
+
+        assert!(webhook.subscribes_to(&EventType::PaymentIntent(PaymentIntentEvent::Succeeded)));
+        assert!(!webhook.subscribes_to(&EventType::PaymentIntent(
+            PaymentIntentEvent::AwaitingCapture
+        )));
+        assert!(!webhook.subscribes_to(&EventType::Refund(RefundEvent::Created)));
+
+        assert!(webhook.subscribes_to_category("payment_intent"));
+        assert!(webhook.subscribes_to_category("checkout_session."));
+        assert!(!webhook.subscribes_to_category("refund"));
+        assert!(!webhook.subscribes_to_category("payment"));
+    }
+
     #[test]
     fn test_create_webhook_builder() {
         let events = vec![EventType::CheckoutSession(CheckoutSessionEvent::Expired)];
@@ -251,4 +366,30 @@ mod tests {
         assert_eq!(evs[0].as_str().unwrap(), "checkout_session.expired");
         assert_eq!(json["description"], "desc");
     }
+
+    #[tokio::test]
+    async fn test_send_test_event() {
+        use crate::types::event::PaymentIntentEvent;
+        use wiremock::matchers::{body_string, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhooks/wh_123/send_test_event"))
+            .and(body_string("event_type=payment_intent.succeeded"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = mock_client(&server);
+        client
+            .webhooks()
+            .send_test_event(
+                &WebhookId::new("wh_123"),
+                EventType::PaymentIntent(PaymentIntentEvent::Succeeded),
+            )
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,91 @@
+//! Retry policies for failed requests.
+//!
+//! The HTTP client consults a [`RetryPolicy`] after every retryable failure to decide whether,
+//! and after how long, to send the request again.
+
+use crate::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Decides how long to wait before retrying a failed request.
+///
+/// Only errors for which [`Error::is_retryable`] returns `true` are passed to the policy;
+/// everything else fails immediately. Error responses such as `500` or `429` and timeouts are
+/// only retried for `GET` and `DELETE` requests and for requests with an idempotency key, since
+/// PayRex may already have applied any other request; connection failures, where the request
+/// never reached PayRex, are retried for every request.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::{Config, Error, RetryPolicy};
+/// use std::time::Duration;
+///
+/// #[derive(Debug)]
+/// struct Constant(Duration);
+///
+/// impl RetryPolicy for Constant {
+///     fn delay(&self, attempt: u32, _error: &Error) -> Option<Duration> {
+///         (attempt <= 5).then_some(self.0)
+///     }
+/// }
+///
+/// let config = Config::builder()
+///     .api_key("sk_test_123")
+///     .retry_policy(Box::new(Constant(Duration::from_secs(1))))
+///     .build()
+///     .unwrap();
+/// ```
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// Returns the delay before retry number `attempt` (starting at 1), or `None` to stop
+    /// retrying and return `error` to the caller.
+    fn delay(&self, attempt: u32, error: &Error) -> Option<Duration>;
+}
+
+/// Exponential backoff: waits `base_delay * 2^(attempt - 1)`, up to `max_retries` times.
+///
+/// This is the default policy, built from [`Config::retry_delay`](crate::Config::retry_delay)
+/// and [`Config::max_retries`](crate::Config::max_retries).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    base_delay: Duration,
+    max_retries: u32,
+}
+
+impl ExponentialBackoff {
+    /// Creates an exponential backoff policy.
+    #[must_use]
+    pub const fn new(base_delay: Duration, max_retries: u32) -> Self {
+        Self {
+            base_delay,
+            max_retries,
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn delay(&self, attempt: u32, _error: &Error) -> Option<Duration> {
+        if attempt > self.max_retries {
+            return None;
+        }
+
+        let multiplier = 2_u32.pow(attempt.saturating_sub(1));
+        Some(self.base_delay * multiplier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff_delay() {
+        let policy = ExponentialBackoff::new(Duration::from_millis(100), 3);
+        let error = Error::Timeout(Duration::from_secs(1));
+
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay(2, &error), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay(3, &error), Some(Duration::from_millis(400)));
+        assert_eq!(policy.delay(4, &error), None);
+    }
+}
@@ -0,0 +1,99 @@
+//! Helpers shared by the unit tests.
+
+use crate::{Client, Config, ConfigBuilder};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Asserts that `value` survives a `to_value` → `from_value` round trip unchanged.
+///
+/// Only response resources are expected to round-trip. Request parameter types are built by
+/// callers and serialized, never deserialized from the API, so they are not covered.
+pub(crate) fn assert_round_trip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_value(value).unwrap();
+    let back: T = serde_json::from_value(json.clone())
+        .unwrap_or_else(|e| panic!("failed to deserialize {json}: {e}"));
+    assert_eq!(
+        &back, value,
+        "round trip changed the value, JSON was {json}"
+    );
+}
+
+/// Returns a config builder pointed at `server` with a test key and retries disabled.
+pub(crate) fn mock_config(server: &wiremock::MockServer) -> ConfigBuilder {
+    Config::builder()
+        .api_key("sk_test_123")
+        .api_base_url(server.uri())
+        .max_retries(0)
+}
+
+/// Returns a client for `server` built from [`mock_config`].
+pub(crate) fn mock_client(server: &wiremock::MockServer) -> Client {
+    Client::with_config(mock_config(server).build().unwrap()).unwrap()
+}
+
+/// Returns the JSON of a paid ₱100.00 card payment `pay_123` of intent `pi_123`.
+///
+/// Tests override the fields they care about before deserializing it.
+pub(crate) fn payment_json() -> serde_json::Value {
+    serde_json::json!({
+        "id": "pay_123",
+        "amount": 10000,
+        "amount_refunded": 0,
+        "currency": "PHP",
+        "fee": 350,
+        "livemode": false,
+        "net_amount": 9650,
+        "payment_intent_id": "pi_123",
+        "status": "paid",
+        "payment_method": { "type": "card" },
+        "refunded": false,
+        "created_at": 1_700_000_000,
+        "updated_at": 1_700_000_100,
+    })
+}
+
+/// Collects the `tracing` output of the current thread while it is alive.
+pub(crate) struct CapturedLogs {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    _guard: tracing::subscriber::DefaultGuard,
+}
+
+impl CapturedLogs {
+    /// Starts capturing, replacing the thread's default subscriber until `self` is dropped.
+    pub(crate) fn start() -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = LogWriter(Arc::clone(&buffer));
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        Self {
+            buffer,
+            _guard: tracing::subscriber::set_default(subscriber),
+        }
+    }
+
+    /// Returns everything logged so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.buffer.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[derive(Clone)]
+struct LogWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 //! Common types and traits used across the SDK.
 
+use crate::types::Timestamp;
 use serde::{Deserialize, Serialize};
 
 pub trait Resource {
@@ -8,6 +9,40 @@ pub trait Resource {
     fn object_type() -> &'static str;
 }
 
+/// A resource whose snapshots can be ordered by `updated_at` and lifecycle status.
+///
+/// Webhooks and polling can deliver snapshots of the same resource out of order; use
+/// [`Snapshot::is_stale_relative_to`] to decide which one to keep.
+pub trait Snapshot {
+    /// The lifecycle status of the resource.
+    type Status: Copy;
+
+    /// When the snapshot was last updated.
+    fn updated_at(&self) -> Timestamp;
+
+    /// The status of the snapshot.
+    fn status(&self) -> Self::Status;
+
+    /// Returns `true` if the lifecycle allows moving from `from` to `to`.
+    fn can_transition(from: Self::Status, to: Self::Status) -> bool;
+
+    /// Returns `true` if this snapshot is older than `other`, a snapshot of the same resource.
+    ///
+    /// Keep `other` when this returns `true` and ignore this one. The later `updated_at` wins.
+    /// When both were updated in the same second, the one further along the lifecycle wins.
+    #[must_use]
+    fn is_stale_relative_to(&self, other: &Self) -> bool {
+        match self.updated_at().cmp(&other.updated_at()) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Greater => false,
+            std::cmp::Ordering::Equal => {
+                Self::can_transition(self.status(), other.status())
+                    && !Self::can_transition(other.status(), self.status())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ObjectType {
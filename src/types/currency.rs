@@ -15,6 +15,15 @@ pub enum Currency {
 }
 
 impl Currency {
+    /// Every currency the PayRex account can transact in.
+    pub const SUPPORTED: &'static [Self] = &[Self::PHP];
+
+    /// Returns `true` if PayRex accepts this currency.
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        Self::SUPPORTED.contains(&self)
+    }
+
     #[must_use]
     pub const fn as_str(self) -> &'static str {
         match self {
@@ -36,6 +45,49 @@ impl Currency {
         }
     }
 
+    /// The smallest amount PayRex accepts for a payment, in the smallest currency unit.
+    ///
+    /// ₱ 20 (2000) for PHP.
+    #[must_use]
+    pub const fn minimum_amount(self) -> i64 {
+        match self {
+            Self::PHP => 2000,
+        }
+    }
+
+    /// The largest amount PayRex accepts for a payment, in the smallest currency unit.
+    ///
+    /// ₱ 59,999,999.99 (5999999999) for PHP.
+    #[must_use]
+    pub const fn maximum_amount(self) -> i64 {
+        match self {
+            Self::PHP => 5_999_999_999,
+        }
+    }
+
+    /// Checks that `amount` is within [`Currency::minimum_amount`] and
+    /// [`Currency::maximum_amount`].
+    ///
+    /// The error message states the bound in this currency, e.g. `minimum is ₱20.00`, so it can
+    /// be shown to the payer as is.
+    pub fn validate_amount(self, amount: i64) -> crate::Result<()> {
+        if amount < self.minimum_amount() {
+            return Err(crate::Error::invalid_request(format!(
+                "amount {} is too low, the minimum is {}",
+                self.format_amount(amount),
+                self.format_amount(self.minimum_amount())
+            )));
+        }
+        if amount > self.maximum_amount() {
+            return Err(crate::Error::invalid_request(format!(
+                "amount {} is too high, the maximum is {}",
+                self.format_amount(amount),
+                self.format_amount(self.maximum_amount())
+            )));
+        }
+        Ok(())
+    }
+
     /// Format an amount in the smallest currency unit (e.g., centavos for PHP).
     ///
     /// # Examples
@@ -79,6 +131,23 @@ impl Default for Currency {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_amount_bounds() {
+        assert_eq!(Currency::PHP.minimum_amount(), 2000);
+        assert_eq!(Currency::PHP.maximum_amount(), 5_999_999_999);
+
+        assert!(Currency::PHP.validate_amount(2000).is_ok());
+        assert!(Currency::PHP.validate_amount(5_999_999_999).is_ok());
+
+        let error = Currency::PHP.validate_amount(1999).unwrap_err();
+        assert!(error.to_string().contains("minimum is ₱20.00"), "{error}");
+        let error = Currency::PHP.validate_amount(6_000_000_000).unwrap_err();
+        assert!(
+            error.to_string().contains("maximum is ₱59999999.99"),
+            "{error}"
+        );
+    }
+
     #[test]
     fn test_currency_as_str() {
         assert_eq!(Currency::PHP.as_str(), "PHP");
@@ -120,6 +189,12 @@ mod tests {
         assert_eq!(currency, Currency::PHP);
     }
 
+    #[test]
+    fn test_currency_is_supported() {
+        assert!(Currency::PHP.is_supported());
+        assert_eq!(Currency::SUPPORTED, &[Currency::PHP]);
+    }
+
     #[test]
     fn test_currency_default() {
         assert_eq!(Currency::default(), Currency::PHP);
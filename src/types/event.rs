@@ -1,9 +1,17 @@
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Display;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
-use crate::types::{EventId, Timestamp};
+use crate::{
+    Error,
+    resources::{
+        billing_statements::BillingStatement, checkout_sessions::CheckoutSession,
+        payment_intents::PaymentIntent, payouts::Payout, refunds::Refund,
+    },
+    types::{EventId, Timestamp},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Event {
@@ -13,13 +21,191 @@ pub struct Event {
     pub event_type: EventType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pending_webhooks: Option<u64>,
+    #[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]
     pub livemode: bool,
+    /// The API version the event payload was rendered with, if PayRex included one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
     //#[serde(skip_serializing_if = "Option::is_none")]
     //pub previous_attributes: Option<Value>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
 
+impl Event {
+    /// Returns `true` if the event was rendered with the `expected` API version or carries no
+    /// version, and logs a `tracing` warning otherwise.
+    ///
+    /// Pass the version your integration was built and tested against. A differing version
+    /// means the payload may have fields your code does not expect, or lack ones it does, so
+    /// deserializing [`Event::resource`] could fail or lose data.
+    pub fn check_api_version(&self, expected: &str) -> bool {
+        match self.api_version.as_deref() {
+            Some(version) if version != expected => {
+                tracing::warn!(
+                    event_id = self.id.as_str(),
+                    api_version = version,
+                    expected_api_version = expected,
+                    "PayRex event API version differs from the expected one"
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if this event happened after `other`.
+    ///
+    /// Webhooks may arrive out of order, so compare events with this instead of relying on
+    /// delivery order. Events created in the same second are ordered by ID.
+    #[must_use]
+    pub fn is_newer_than(&self, other: &Event) -> bool {
+        (self.created_at, self.id.as_str()) > (other.created_at, other.id.as_str())
+    }
+
+    /// Returns the resource the event is about.
+    ///
+    /// PayRex nests the resource under `data.resource`; payloads without that wrapper are
+    /// returned as-is.
+    #[must_use]
+    pub fn resource(&self) -> &Value {
+        self.data.get("resource").unwrap_or(&self.data)
+    }
+}
+
+/// Implements `TryFrom<&Event>` for a resource delivered by events of the given `EventType`
+/// variant.
+macro_rules! impl_try_from_event {
+    ($resource:ty, $variant:ident, $name:expr) => {
+        impl TryFrom<&Event> for $resource {
+            type Error = Error;
+
+            fn try_from(event: &Event) -> crate::Result<Self> {
+                if !matches!(event.event_type, EventType::$variant(_)) {
+                    return Err(Error::invalid_request(format!(
+                        "expected a {} event, got {}",
+                        $name, event.event_type
+                    )));
+                }
+                Ok(<$resource>::deserialize(event.resource())?)
+            }
+        }
+    };
+}
+
+impl_try_from_event!(BillingStatement, BillingStatement, "billing_statement");
+impl_try_from_event!(CheckoutSession, CheckoutSession, "checkout_session");
+impl_try_from_event!(PaymentIntent, PaymentIntent, "payment_intent");
+impl_try_from_event!(Payout, Payout, "payout");
+impl_try_from_event!(Refund, Refund, "refund");
+
+/// Storage for the event IDs an [`EventDeduper`] has already seen.
+///
+/// Implement this to back deduplication with a shared store (e.g. Redis or a database table)
+/// when several processes handle webhooks.
+pub trait SeenEvents {
+    /// Records `id` as seen, returning `true` if it had already been recorded.
+    fn check_and_insert(&mut self, id: &EventId) -> bool;
+}
+
+/// An in-memory [`SeenEvents`] store that remembers the most recently seen event IDs.
+///
+/// Once `capacity` IDs are stored, the least recently seen ID is evicted.
+#[derive(Debug, Clone)]
+pub struct InMemorySeenEvents {
+    capacity: usize,
+    ids: HashSet<EventId>,
+    order: VecDeque<EventId>,
+}
+
+impl InMemorySeenEvents {
+    /// Creates a store that remembers up to `capacity` event IDs.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ids: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+}
+
+impl SeenEvents for InMemorySeenEvents {
+    fn check_and_insert(&mut self, id: &EventId) -> bool {
+        if self.ids.contains(id) {
+            if let Some(position) = self.order.iter().position(|seen| seen == id) {
+                self.order.remove(position);
+            }
+            self.order.push_back(id.clone());
+            return true;
+        }
+
+        if self.capacity == 0 {
+            return false;
+        }
+
+        if self.order.len() == self.capacity
+            && let Some(evicted) = self.order.pop_front()
+        {
+            self.ids.remove(&evicted);
+        }
+        self.ids.insert(id.clone());
+        self.order.push_back(id.clone());
+        false
+    }
+}
+
+/// Skips events that were already delivered.
+///
+/// PayRex delivers webhooks at least once, so the same event can arrive more than once.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::types::event::EventDeduper;
+/// use payrex::types::EventId;
+///
+/// let mut deduper = EventDeduper::new(1000);
+/// let id = EventId::new("evt_123");
+///
+/// assert!(!deduper.is_duplicate(&id));
+/// assert!(deduper.is_duplicate(&id));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventDeduper<S = InMemorySeenEvents> {
+    store: S,
+}
+
+impl EventDeduper {
+    /// Creates a deduper that remembers the last `capacity` event IDs in memory.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_store(InMemorySeenEvents::new(capacity))
+    }
+}
+
+impl<S: SeenEvents> EventDeduper<S> {
+    /// Creates a deduper backed by a custom store.
+    pub const fn with_store(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Records the event ID and returns `true` if it was seen before.
+    pub fn is_duplicate(&mut self, id: &EventId) -> bool {
+        self.store.check_and_insert(id)
+    }
+
+    /// Returns the event if it has not been seen before.
+    pub fn filter(&mut self, event: Event) -> Option<Event> {
+        (!self.is_duplicate(&event.id)).then_some(event)
+    }
+
+    /// Returns the underlying store.
+    pub const fn store(&self) -> &S {
+        &self.store
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EventType {
     BillingStatement(BillingStatementEvent),
@@ -84,17 +270,18 @@ impl Serialize for EventType {
     where
         S: Serializer,
     {
-        let s = match self {
-            EventType::BillingStatement(e) => format!("billing_statement.{e:?}"),
+        let (prefix, event) = match self {
+            EventType::BillingStatement(e) => ("billing_statement", serde_plain::to_string(e)),
             EventType::BillingStatementLineItem(e) => {
-                format!("billing_statement_line_item.{e:?}")
+                ("billing_statement_line_item", serde_plain::to_string(e))
             }
-            EventType::CheckoutSession(e) => format!("checkout_session.{e:?}"),
-            EventType::PaymentIntent(e) => format!("payment_intent.{e:?}"),
-            EventType::Payout(e) => format!("payout.{e:?}"),
-            EventType::Refund(e) => format!("refund.{e:?}"),
+            EventType::CheckoutSession(e) => ("checkout_session", serde_plain::to_string(e)),
+            EventType::PaymentIntent(e) => ("payment_intent", serde_plain::to_string(e)),
+            EventType::Payout(e) => ("payout", serde_plain::to_string(e)),
+            EventType::Refund(e) => ("refund", serde_plain::to_string(e)),
         };
-        serializer.serialize_str(&s.to_lowercase())
+        let event = event.map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&format!("{prefix}.{event}"))
     }
 }
 
@@ -135,6 +322,20 @@ impl<'de> Deserialize<'de> for EventType {
 }
 
 impl EventType {
+    /// Returns the resource prefix of the event type, e.g. `payment_intent` for
+    /// `payment_intent.succeeded`.
+    #[must_use]
+    pub const fn category(&self) -> &'static str {
+        match self {
+            EventType::BillingStatement(_) => "billing_statement",
+            EventType::BillingStatementLineItem(_) => "billing_statement_line_item",
+            EventType::CheckoutSession(_) => "checkout_session",
+            EventType::PaymentIntent(_) => "payment_intent",
+            EventType::Payout(_) => "payout",
+            EventType::Refund(_) => "refund",
+        }
+    }
+
     #[must_use]
     pub fn as_str(&self) -> String {
         serde_plain::to_string(&self).unwrap()
@@ -150,6 +351,7 @@ impl Display for EventType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::assert_round_trip;
     use serde_json::json;
 
     #[test]
@@ -169,6 +371,145 @@ mod tests {
         assert_eq!(serde_json::to_string(&et2).unwrap(), "\"refund.updated\"");
     }
 
+    #[test]
+    fn test_event_type_round_trip() {
+        use BillingStatementEvent as Bs;
+
+        let event_types = [
+            EventType::BillingStatement(Bs::Created),
+            EventType::BillingStatement(Bs::Updated),
+            EventType::BillingStatement(Bs::Deleted),
+            EventType::BillingStatement(Bs::Finalized),
+            EventType::BillingStatement(Bs::Sent),
+            EventType::BillingStatement(Bs::MarkedUncollectible),
+            EventType::BillingStatement(Bs::Voided),
+            EventType::BillingStatement(Bs::Paid),
+            EventType::BillingStatement(Bs::WillBeDue),
+            EventType::BillingStatement(Bs::Overdue),
+            EventType::BillingStatementLineItem(BillingStatementLineItemEvent::Created),
+            EventType::BillingStatementLineItem(BillingStatementLineItemEvent::Updated),
+            EventType::BillingStatementLineItem(BillingStatementLineItemEvent::Deleted),
+            EventType::CheckoutSession(CheckoutSessionEvent::Expired),
+            EventType::PaymentIntent(PaymentIntentEvent::AwaitingCapture),
+            EventType::PaymentIntent(PaymentIntentEvent::Succeeded),
+            EventType::Payout(PayoutEvent::Deposited),
+            EventType::Refund(RefundEvent::Created),
+            EventType::Refund(RefundEvent::Updated),
+        ];
+
+        for event_type in &event_types {
+            assert_round_trip(event_type);
+        }
+
+        assert_eq!(
+            EventType::BillingStatement(Bs::MarkedUncollectible).as_str(),
+            "billing_statement.marked_uncollectible"
+        );
+    }
+
+    fn event(id: &str, created_at: i64) -> Event {
+        Event {
+            id: EventId::new(id),
+            data: json!({}),
+            event_type: EventType::Refund(RefundEvent::Created),
+            pending_webhooks: None,
+            livemode: false,
+            api_version: None,
+            created_at: Timestamp::from_unix(created_at),
+            updated_at: Timestamp::from_unix(created_at),
+        }
+    }
+
+    #[test]
+    fn test_event_is_newer_than() {
+        let older = event("evt_b", 1_600_000_000);
+        let newer = event("evt_a", 1_600_000_100);
+        assert!(newer.is_newer_than(&older));
+        assert!(!older.is_newer_than(&newer));
+
+        let same_second = event("evt_c", 1_600_000_000);
+        assert!(same_second.is_newer_than(&older));
+        assert!(!older.is_newer_than(&same_second));
+        assert!(!older.is_newer_than(&older));
+    }
+
+    #[test]
+    fn test_event_deduper_skips_duplicates() {
+        let mut deduper = EventDeduper::new(10);
+
+        assert!(deduper.filter(event("evt_1", 1)).is_some());
+        assert!(deduper.filter(event("evt_2", 2)).is_some());
+        assert!(deduper.filter(event("evt_1", 1)).is_none());
+        assert!(deduper.is_duplicate(&EventId::new("evt_2")));
+    }
+
+    #[test]
+    fn test_event_deduper_evicts_least_recently_seen() {
+        let mut deduper = EventDeduper::new(2);
+        let (a, b, c) = (
+            EventId::new("evt_a"),
+            EventId::new("evt_b"),
+            EventId::new("evt_c"),
+        );
+
+        assert!(!deduper.is_duplicate(&a));
+        assert!(!deduper.is_duplicate(&b));
+        // Seeing `a` again makes `b` the least recently seen.
+        assert!(deduper.is_duplicate(&a));
+        assert!(!deduper.is_duplicate(&c));
+
+        assert!(deduper.is_duplicate(&a));
+        assert!(!deduper.is_duplicate(&b));
+    }
+
+    #[test]
+    fn test_try_from_event() {
+        let refund = json!({
+            "id": "re_123",
+            "amount": 1000,
+            "currency": "PHP",
+            "livemode": false,
+            "status": "succeeded",
+            "reason": "others",
+            "payment_id": "pay_123",
+            "created_at": 1_600_000_000,
+            "updated_at": 1_600_000_000,
+        });
+        let mut event = event("evt_1", 1_600_000_000);
+        event.data = json!({ "resource": refund });
+
+        let parsed = Refund::try_from(&event).unwrap();
+        assert_eq!(parsed.id.as_str(), "re_123");
+
+        event.data = refund;
+        let parsed: Refund = (&event).try_into().unwrap();
+        assert_eq!(parsed.amount, 1000);
+    }
+
+    #[test]
+    fn test_try_from_event_type_mismatch() {
+        let mut event = event("evt_1", 1_600_000_000);
+        event.event_type = EventType::Payout(PayoutEvent::Deposited);
+
+        let result = Refund::try_from(&event);
+        assert!(matches!(result, Err(Error::InvalidRequest(m)) if m.contains("refund")));
+        assert!(PaymentIntent::try_from(&event).is_err());
+    }
+
+    #[test]
+    fn test_event_livemode_from_integer() {
+        let event: Event = serde_json::from_value(json!({
+            "id": "evt_123",
+            "data": {},
+            "type": "refund.created",
+            "livemode": 1,
+            "created_at": 1_600_000_000,
+            "updated_at": 1_600_000_000,
+        }))
+        .unwrap();
+        assert!(event.livemode);
+    }
+
     #[test]
     fn test_event_serialization() {
         let id = EventId::new("evt_123");
@@ -179,17 +520,65 @@ mod tests {
             event_type: EventType::CheckoutSession(CheckoutSessionEvent::Expired),
             pending_webhooks: Some(3),
             livemode: false,
+            api_version: Some("2024-01-01".to_string()),
             created_at: Timestamp::from_unix(1_600_000_000),
             updated_at: Timestamp::from_unix(1_600_000_500),
         };
 
+        assert_round_trip(&event);
+
         let json = serde_json::to_value(&event).unwrap();
         assert_eq!(json["id"], id.as_str());
         assert_eq!(json["data"], data);
         assert_eq!(json["type"], "checkout_session.expired");
         assert_eq!(json["pending_webhooks"], 3);
         assert_eq!(json["livemode"], false);
+        assert_eq!(json["api_version"], "2024-01-01");
         assert_eq!(json["created_at"], 1_600_000_000);
         assert_eq!(json["updated_at"], 1_600_000_500);
     }
+
+    #[test]
+    fn test_event_api_version_deserialization() {
+        let mut payload = json!({
+            "id": "evt_123",
+            "data": {},
+            "type": "refund.created",
+            "livemode": false,
+            "api_version": "2099-01-01",
+            "created_at": 1_600_000_000,
+            "updated_at": 1_600_000_000,
+        });
+        let event: Event = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(event.api_version.as_deref(), Some("2099-01-01"));
+
+        payload.as_object_mut().unwrap().remove("api_version");
+        let event: Event = serde_json::from_value(payload).unwrap();
+        assert_eq!(event.api_version, None);
+        assert!(
+            !serde_json::to_value(&event)
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .contains_key("api_version")
+        );
+    }
+
+    #[test]
+    fn test_event_check_api_version_warns() {
+        let captured = crate::test_utils::CapturedLogs::start();
+
+        let mut event = event("evt_1", 1_600_000_000);
+        assert!(event.check_api_version("2024-01-01"));
+        event.api_version = Some("2024-01-01".to_string());
+        assert!(event.check_api_version("2024-01-01"));
+        assert!(captured.contents().is_empty());
+
+        event.api_version = Some("2099-01-01".to_string());
+        assert!(!event.check_api_version("2024-01-01"));
+        let logs = captured.contents();
+        assert!(logs.contains("API version differs"));
+        assert!(logs.contains("2099-01-01"));
+        assert!(logs.contains("evt_1"));
+    }
 }
@@ -0,0 +1,145 @@
+//! Fee estimation for PayRex payments.
+//!
+//! PayRex charges a percentage of the amount, plus a fixed fee for some payment methods. Rates
+//! are negotiated per account, so [`FeeSchedule::default`] only reflects the standard published
+//! rates; override them with [`FeeSchedule::with_rate`] to match your account.
+
+use std::collections::HashMap;
+
+use crate::types::PaymentMethod;
+
+/// The fee charged for one payment method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRate {
+    /// The percentage part of the fee in basis points, e.g. `350` for 3.5%.
+    pub basis_points: u32,
+
+    /// The fixed part of the fee in the smallest currency unit, e.g. `1500` for ₱ 15.
+    pub fixed: i64,
+}
+
+impl FeeRate {
+    /// Creates a rate of `basis_points` hundredths of a percent plus `fixed` cents.
+    #[must_use]
+    pub const fn new(basis_points: u32, fixed: i64) -> Self {
+        Self {
+            basis_points,
+            fixed,
+        }
+    }
+
+    /// Returns the fee for `amount`, rounding the percentage part half up to the nearest cent.
+    #[must_use]
+    pub fn fee_for(self, amount: i64) -> i64 {
+        let percentage = (i128::from(amount) * i128::from(self.basis_points) + 5_000) / 10_000;
+        i64::try_from(percentage)
+            .unwrap_or(i64::MAX)
+            .saturating_add(self.fixed)
+    }
+}
+
+/// Fee rates per payment method, used to estimate the PayRex fee before charging.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::types::{FeeRate, FeeSchedule, PaymentMethod};
+///
+/// let schedule = FeeSchedule::default().with_rate(PaymentMethod::GCash, FeeRate::new(200, 0));
+/// assert_eq!(schedule.estimate(100_000, &PaymentMethod::GCash), Some(2_000));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeSchedule {
+    rates: HashMap<PaymentMethod, FeeRate>,
+}
+
+impl FeeSchedule {
+    /// Creates a schedule with no rates.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Sets the rate for `method`, replacing any existing one.
+    #[must_use]
+    pub fn with_rate(mut self, method: PaymentMethod, rate: FeeRate) -> Self {
+        self.rates.insert(method, rate);
+        self
+    }
+
+    /// Returns the rate for `method`, if the schedule has one.
+    #[must_use]
+    pub fn rate(&self, method: &PaymentMethod) -> Option<FeeRate> {
+        self.rates.get(method).copied()
+    }
+
+    /// Estimates the fee for charging `amount` with `method`.
+    ///
+    /// Returns `None` when the schedule has no rate for `method`.
+    #[must_use]
+    pub fn estimate(&self, amount: i64, method: &PaymentMethod) -> Option<i64> {
+        self.rate(method).map(|rate| rate.fee_for(amount))
+    }
+}
+
+impl Default for FeeSchedule {
+    /// PayRex's standard published rates: cards at 3.5% + ₱ 15, GCash at 2.5%, Maya at 2% and
+    /// QR Ph at 1.5%.
+    fn default() -> Self {
+        Self::empty()
+            .with_rate(PaymentMethod::Card, FeeRate::new(350, 1_500))
+            .with_rate(PaymentMethod::GCash, FeeRate::new(250, 0))
+            .with_rate(PaymentMethod::Maya, FeeRate::new(200, 0))
+            .with_rate(PaymentMethod::QRPh, FeeRate::new(150, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_schedule_card_fee() {
+        let schedule = FeeSchedule::default();
+
+        assert_eq!(
+            schedule.estimate(100_000, &PaymentMethod::Card),
+            Some(5_000)
+        );
+        // 3.5% of ₱ 120.50 is ₱ 4.2175, rounded to ₱ 4.22.
+        assert_eq!(schedule.estimate(12_050, &PaymentMethod::Card), Some(1_922));
+    }
+
+    #[test]
+    fn test_default_schedule_gcash_fee() {
+        let schedule = FeeSchedule::default();
+
+        assert_eq!(
+            schedule.estimate(100_000, &PaymentMethod::GCash),
+            Some(2_500)
+        );
+        // 2.5% of ₱ 20.10 is ₱ 0.5025, rounded to ₱ 0.50.
+        assert_eq!(schedule.estimate(2_010, &PaymentMethod::GCash), Some(50));
+    }
+
+    #[test]
+    fn test_schedule_overrides_and_unknown_methods() {
+        let schedule =
+            FeeSchedule::default().with_rate(PaymentMethod::Card, FeeRate::new(299, 1_000));
+        assert_eq!(
+            schedule.estimate(100_000, &PaymentMethod::Card),
+            Some(3_990)
+        );
+
+        let unknown = PaymentMethod::Unknown("billease".to_string());
+        assert_eq!(schedule.estimate(100_000, &unknown), None);
+        assert_eq!(FeeSchedule::empty().rate(&PaymentMethod::Card), None);
+    }
+
+    #[test]
+    fn test_fee_rate_saturates() {
+        assert_eq!(FeeRate::new(10_000, 1).fee_for(i64::MAX), i64::MAX);
+    }
+}
@@ -0,0 +1,132 @@
+//! Lenient deserialization for boolean flags such as `livemode`.
+//!
+//! Accepts `true`/`false`, `1`/`0` and `"true"`/`"false"`, so a payload that encodes booleans
+//! differently (e.g. a replayed webhook) still deserializes. Use with
+//! `#[serde(deserialize_with = "crate::types::lenient_bool::deserialize")]`, or with
+//! `default` and `lenient_bool::option::deserialize` for `Option<bool>` fields.
+
+use serde::{Deserialize, Deserializer, de};
+use std::fmt;
+
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    LenientBool::deserialize(deserializer).map(|value| value.0)
+}
+
+/// Reads a lenient boolean from an already parsed JSON `value`, or `None` if it is not one.
+pub(crate) fn from_value(value: &serde_json::Value) -> Option<bool> {
+    deserialize(value).ok()
+}
+
+pub(crate) mod option {
+    use super::LenientBool;
+    use serde::{Deserialize, Deserializer};
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<LenientBool>::deserialize(deserializer).map(|value| value.map(|value| value.0))
+    }
+}
+
+struct LenientBool(bool);
+
+impl<'de> Deserialize<'de> for LenientBool {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LenientBoolVisitor;
+
+        impl de::Visitor<'_> for LenientBoolVisitor {
+            type Value = LenientBool;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a boolean, 0 or 1, or \"true\" or \"false\"")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(LenientBool(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                match v {
+                    0 => Ok(LenientBool(false)),
+                    1 => Ok(LenientBool(true)),
+                    _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                match v {
+                    0 => Ok(LenientBool(false)),
+                    1 => Ok(LenientBool(true)),
+                    _ => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match v {
+                    "true" | "1" => Ok(LenientBool(true)),
+                    "false" | "0" => Ok(LenientBool(false)),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(LenientBoolVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Flags {
+        #[serde(deserialize_with = "super::deserialize")]
+        livemode: bool,
+        #[serde(default, deserialize_with = "super::option::deserialize")]
+        optional: Option<bool>,
+    }
+
+    fn livemode(value: serde_json::Value) -> serde_json::Result<bool> {
+        serde_json::from_value::<Flags>(serde_json::json!({ "livemode": value }))
+            .map(|flags| flags.livemode)
+    }
+
+    #[test]
+    fn test_lenient_bool_representations() {
+        assert!(livemode(true.into()).unwrap());
+        assert!(!livemode(false.into()).unwrap());
+        assert!(livemode(1.into()).unwrap());
+        assert!(!livemode(0.into()).unwrap());
+        assert!(livemode("true".into()).unwrap());
+        assert!(!livemode("false".into()).unwrap());
+    }
+
+    #[test]
+    fn test_lenient_bool_rejects_other_values() {
+        assert!(livemode(2.into()).is_err());
+        assert!(livemode((-1).into()).is_err());
+        assert!(livemode("yes".into()).is_err());
+        assert!(livemode(serde_json::Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_lenient_bool_option() {
+        let flags: Flags =
+            serde_json::from_value(serde_json::json!({ "livemode": 1, "optional": 0 })).unwrap();
+        assert_eq!(flags.optional, Some(false));
+
+        let flags: Flags =
+            serde_json::from_value(serde_json::json!({ "livemode": 1, "optional": null })).unwrap();
+        assert_eq!(flags.optional, None);
+
+        let flags: Flags = serde_json::from_value(serde_json::json!({ "livemode": 1 })).unwrap();
+        assert_eq!(flags.optional, None);
+    }
+}
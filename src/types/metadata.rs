@@ -2,8 +2,10 @@
 //!
 //! Metadata allows you to store additional structured information on PayRex objects.
 
-use serde::{Deserialize, Serialize};
+use crate::Error;
+use serde::{Deserialize, Deserializer, Serialize, de};
 use std::collections::HashMap;
+use std::fmt;
 
 /// Metadata is a set of key-value pairs that you can attach to an object.
 ///
@@ -20,11 +22,17 @@ use std::collections::HashMap;
 /// metadata.insert("order_id", "12345");
 /// metadata.insert("customer_note", "VIP customer");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+///
+/// Deserialization is lenient: numbers and booleans are stored as their string representation
+/// and `null` values are dropped, so a stray non-string value does not fail the whole resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Default)]
 #[serde(transparent)]
 pub struct Metadata(HashMap<String, String>);
 
 impl Metadata {
+    /// The maximum length of a metadata key, in characters.
+    pub const MAX_KEY_LENGTH: usize = 40;
+
     #[must_use]
     pub fn new() -> Self {
         Self(HashMap::new())
@@ -41,6 +49,40 @@ impl Metadata {
         self.0.insert(key.into(), value.into());
     }
 
+    /// Inserts a key-value pair after checking the key with [`Metadata::validate_key`].
+    ///
+    /// Prefer this over [`Metadata::insert`] for keys that come from user input.
+    pub fn try_insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> crate::Result<()> {
+        let key = key.into();
+        Self::validate_key(&key)?;
+        self.0.insert(key, value.into());
+        Ok(())
+    }
+
+    /// Checks that `key` is non-empty, at most [`Metadata::MAX_KEY_LENGTH`] characters, and
+    /// free of `[` and `]`, which would break the `metadata[key]=value` form encoding.
+    pub fn validate_key(key: &str) -> crate::Result<()> {
+        if key.is_empty() {
+            return Err(Error::invalid_request("metadata key cannot be empty"));
+        }
+        if key.chars().count() > Self::MAX_KEY_LENGTH {
+            return Err(Error::invalid_request(format!(
+                "metadata key `{key}` is longer than {} characters",
+                Self::MAX_KEY_LENGTH
+            )));
+        }
+        if key.contains(['[', ']']) {
+            return Err(Error::invalid_request(format!(
+                "metadata key `{key}` cannot contain `[` or `]`"
+            )));
+        }
+        Ok(())
+    }
+
     #[must_use]
     pub fn get(&self, key: &str) -> Option<&str> {
         self.0.get(key).map(String::as_str)
@@ -72,6 +114,82 @@ impl Metadata {
     pub fn clear(&mut self) {
         self.0.clear();
     }
+
+    /// Copies every entry of `defaults` whose key is not already present.
+    ///
+    /// Existing values win, so the caller's own keys are never overwritten.
+    pub fn merge_defaults(&mut self, defaults: &Self) {
+        for (key, value) in defaults {
+            self.0.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Metadata {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = HashMap::<String, LenientValue>::deserialize(deserializer)?;
+        Ok(map
+            .into_iter()
+            .filter_map(|(key, value)| value.0.map(|value| (key, value)))
+            .collect())
+    }
+}
+
+/// A metadata value that accepts strings, numbers, booleans, and `null`.
+struct LenientValue(Option<String>);
+
+impl<'de> Deserialize<'de> for LenientValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LenientVisitor;
+
+        impl de::Visitor<'_> for LenientVisitor {
+            type Value = LenientValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string, number, boolean, or null")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v.to_string())))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v)))
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v.to_string())))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v.to_string())))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v.to_string())))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(LenientValue(Some(v.to_string())))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(LenientValue(None))
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(LenientValue(None))
+            }
+        }
+
+        deserializer.deserialize_any(LenientVisitor)
+    }
 }
 
 impl From<HashMap<String, String>> for Metadata {
@@ -80,6 +198,15 @@ impl From<HashMap<String, String>> for Metadata {
     }
 }
 
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for Metadata {
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect()
+    }
+}
+
 impl From<Metadata> for HashMap<String, String> {
     fn from(metadata: Metadata) -> Self {
         metadata.0
@@ -112,6 +239,42 @@ mod tests {
         assert_eq!(metadata.len(), 0);
     }
 
+    #[test]
+    fn test_metadata_from_array() {
+        let metadata = Metadata::from([("order_id", "12345"), ("source", "web")]);
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata.get("order_id"), Some("12345"));
+        assert_eq!(metadata.get("source"), Some("web"));
+
+        let empty: Metadata = Metadata::from([] as [(&str, &str); 0]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_metadata_try_insert_rejects_invalid_keys() {
+        let mut metadata = Metadata::new();
+
+        assert!(metadata.try_insert("order_id", "12345").is_ok());
+        assert_eq!(metadata.get("order_id"), Some("12345"));
+
+        for key in ["order]id", "order[id", ""] {
+            let result = metadata.try_insert(key, "x");
+            assert!(matches!(result, Err(Error::InvalidRequest(_))), "{key:?}");
+        }
+
+        let long_key = "k".repeat(Metadata::MAX_KEY_LENGTH + 1);
+        assert!(matches!(
+            metadata.try_insert(long_key, "x"),
+            Err(Error::InvalidRequest(m)) if m.contains("longer than")
+        ));
+        assert!(
+            metadata
+                .try_insert("k".repeat(Metadata::MAX_KEY_LENGTH), "x")
+                .is_ok()
+        );
+        assert_eq!(metadata.len(), 2);
+    }
+
     #[test]
     fn test_metadata_with_pair() {
         let metadata = Metadata::with_pair("key", "value");
@@ -159,6 +322,17 @@ mod tests {
         assert!(metadata.is_empty());
     }
 
+    #[test]
+    fn test_metadata_merge_defaults() {
+        let mut metadata = Metadata::from([("tenant_id", "globex"), ("order", "42")]);
+        metadata.merge_defaults(&Metadata::from([("tenant_id", "acme"), ("source", "sdk")]));
+
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata.get("tenant_id"), Some("globex"));
+        assert_eq!(metadata.get("source"), Some("sdk"));
+        assert_eq!(metadata.get("order"), Some("42"));
+    }
+
     #[test]
     fn test_metadata_from_hashmap() {
         let mut map = HashMap::new();
@@ -186,4 +360,23 @@ mod tests {
         assert_eq!(metadata.get("order_id"), Some("12345"));
         assert_eq!(metadata.get("note"), Some("test"));
     }
+
+    #[test]
+    fn test_metadata_lenient_deserialization() {
+        let json = r#"{"a":"x","b":1,"c":true,"d":-2.5,"e":null}"#;
+        let metadata: Metadata = serde_json::from_str(json).unwrap();
+
+        assert_eq!(metadata.get("a"), Some("x"));
+        assert_eq!(metadata.get("b"), Some("1"));
+        assert_eq!(metadata.get("c"), Some("true"));
+        assert_eq!(metadata.get("d"), Some("-2.5"));
+        assert!(!metadata.contains_key("e"));
+        assert_eq!(metadata.len(), 4);
+    }
+
+    #[test]
+    fn test_metadata_rejects_nested_values() {
+        let json = r#"{"a":{"nested":"x"}}"#;
+        assert!(serde_json::from_str::<Metadata>(json).is_err());
+    }
 }
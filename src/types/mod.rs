@@ -6,17 +6,26 @@
 pub mod common;
 pub mod currency;
 pub mod event;
+pub mod fees;
 pub mod ids;
+pub(crate) mod lenient_bool;
 pub mod metadata;
+pub mod money;
 pub mod pagination;
+pub mod patch;
 pub mod payment_methods;
+pub mod secret;
 pub mod timestamp;
 
 // Re-export commonly used types
 pub use common::*;
 pub use currency::Currency;
+pub use fees::{FeeRate, FeeSchedule};
 pub use ids::*;
 pub use metadata::Metadata;
-pub use pagination::{List, ListParams};
+pub use money::Money;
+pub use pagination::{List, ListParams, ListQuery, ListStream, Paginator};
+pub use patch::Patch;
 pub use payment_methods::*;
+pub use secret::Secret;
 pub use timestamp::Timestamp;
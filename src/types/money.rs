@@ -0,0 +1,169 @@
+//! Currency-aware amounts.
+//!
+//! [`Money`] pairs an amount with its [`Currency`] so arithmetic and comparisons between
+//! different currencies are rejected instead of producing meaningless results.
+
+use crate::{Error, Result, types::Currency};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// An amount in the smallest unit of its currency (e.g., centavos for PHP).
+///
+/// Amounts of different currencies cannot be added, subtracted or compared: the checked
+/// operations return an error and [`PartialOrd`] returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::types::{Currency, Money};
+///
+/// let total = Money::new(10_000, Currency::PHP);
+/// let refunded = Money::new(2_500, Currency::PHP);
+///
+/// let refundable = total.checked_sub(refunded)?;
+/// assert_eq!(refundable, Money::new(7_500, Currency::PHP));
+/// assert!(refundable < total);
+/// # Ok::<(), payrex::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    amount: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates an amount in the smallest unit of `currency`.
+    #[must_use]
+    pub const fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Returns a zero amount in `currency`.
+    #[must_use]
+    pub const fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Returns the amount in the smallest currency unit.
+    #[must_use]
+    pub const fn amount(self) -> i64 {
+        self.amount
+    }
+
+    /// Returns the currency of the amount.
+    #[must_use]
+    pub const fn currency(self) -> Currency {
+        self.currency
+    }
+
+    /// Adds two amounts of the same currency.
+    ///
+    /// Returns [`Error::InvalidRequest`] if the currencies differ or the sum overflows.
+    pub fn checked_add(self, other: Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or_else(|| Error::invalid_request("amount overflow"))
+    }
+
+    /// Subtracts an amount of the same currency.
+    ///
+    /// Returns [`Error::InvalidRequest`] if the currencies differ or the difference overflows.
+    pub fn checked_sub(self, other: Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Self::new(amount, self.currency))
+            .ok_or_else(|| Error::invalid_request("amount overflow"))
+    }
+
+    /// Compares two amounts of the same currency.
+    ///
+    /// Returns [`Error::InvalidRequest`] if the currencies differ.
+    pub fn try_cmp(self, other: Self) -> Result<Ordering> {
+        self.ensure_same_currency(other)?;
+        Ok(self.amount.cmp(&other.amount))
+    }
+
+    fn ensure_same_currency(self, other: Self) -> Result<()> {
+        ensure_same(self.currency, other.currency)
+    }
+}
+
+/// Rejects two different currencies.
+///
+/// Generic so the mismatch can be tested while [`Currency`] has a single variant.
+fn ensure_same<C: PartialEq + fmt::Display>(a: C, b: C) -> Result<()> {
+    if a == b {
+        Ok(())
+    } else {
+        Err(Error::invalid_request(format!(
+            "currency mismatch: {a} and {b}"
+        )))
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.try_cmp(*other).ok()
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.currency.format_amount(self.amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_currency_arithmetic() {
+        let a = Money::new(10_000, Currency::PHP);
+        let b = Money::new(2_500, Currency::PHP);
+
+        assert_eq!(a.checked_add(b).unwrap(), Money::new(12_500, Currency::PHP));
+        assert_eq!(a.checked_sub(b).unwrap(), Money::new(7_500, Currency::PHP));
+        assert_eq!(a.try_cmp(b).unwrap(), Ordering::Greater);
+        assert!(b < a);
+        assert_eq!(a.to_string(), "₱100.00");
+    }
+
+    #[test]
+    fn test_overflow_is_rejected() {
+        let max = Money::new(i64::MAX, Currency::PHP);
+        let one = Money::new(1, Currency::PHP);
+
+        assert!(matches!(
+            max.checked_add(one),
+            Err(Error::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            Money::new(i64::MIN, Currency::PHP).checked_sub(one),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_currency_mismatch_is_rejected() {
+        assert!(ensure_same("PHP", "PHP").is_ok());
+        assert!(matches!(
+            ensure_same("PHP", "USD"),
+            Err(Error::InvalidRequest(m)) if m == "currency mismatch: PHP and USD"
+        ));
+    }
+
+    #[test]
+    fn test_same_currency_guard() {
+        let a = Money::new(100, Currency::PHP);
+        assert!(a.ensure_same_currency(Money::zero(Currency::PHP)).is_ok());
+        assert_eq!(
+            a.partial_cmp(&Money::zero(Currency::PHP)),
+            Some(Ordering::Greater)
+        );
+    }
+}
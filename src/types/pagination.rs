@@ -2,7 +2,9 @@
 //!
 //! PayRex uses cursor-based pagination for list endpoints.
 
-use serde::{Deserialize, Serialize};
+use crate::{Error, Result, http::HttpClient, types::Resource};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct List<T> {
@@ -41,6 +43,46 @@ impl<T> List<T> {
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.data.iter()
     }
+
+    /// Returns the items of this page as a slice.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: Serialize> List<T> {
+    /// Writes each item as one line of JSON (NDJSON), e.g. for export to an accounting system.
+    ///
+    /// Only the items are written; `has_more` and the other list fields are not.
+    pub fn write_ndjson<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        for item in &self.data {
+            serde_json::to_writer(&mut writer, item)?;
+            writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the items as newline-delimited JSON. See [`List::write_ndjson`].
+    pub fn to_ndjson(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        self.write_ndjson(&mut buffer)?;
+        Ok(String::from_utf8(buffer).expect("serde_json writes valid UTF-8"))
+    }
+}
+
+impl<T> AsRef<[T]> for List<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> std::ops::Deref for List<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
 }
 
 impl<T> Default for List<T> {
@@ -67,6 +109,9 @@ impl<'a, T> IntoIterator for &'a List<T> {
     }
 }
 
+/// Pagination parameters for list endpoints.
+///
+/// `after` and `before` are mutually exclusive: set at most one cursor per request.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ListParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,11 +149,249 @@ impl ListParams {
         self.before = Some(id.into());
         self
     }
+
+    /// Checks that at most one of the `after` and `before` cursors is set.
+    pub fn validate(&self) -> Result<()> {
+        if self.after.is_some() && self.before.is_some() {
+            return Err(Error::invalid_request(
+                "`after` and `before` cannot be set at the same time",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Query parameters of a list endpoint that carry the pagination cursors.
+///
+/// Implemented by [`ListParams`] and by the endpoint-specific params that flatten it, such as
+/// [`RefundListParams`](crate::resources::refunds::RefundListParams), so that a [`ListStream`] can move
+/// the cursor while keeping the endpoint's filters.
+pub trait ListQuery: Serialize {
+    /// Returns the pagination parameters.
+    fn list_params(&self) -> &ListParams;
+
+    /// Returns the pagination parameters for the stream to update.
+    fn list_params_mut(&mut self) -> &mut ListParams;
+}
+
+impl ListQuery for ListParams {
+    fn list_params(&self) -> &ListParams {
+        self
+    }
+
+    fn list_params_mut(&mut self) -> &mut ListParams {
+        self
+    }
+}
+
+/// Item-by-item iteration over every page of a list endpoint.
+///
+/// Pages are fetched lazily: the next page is requested only once every item of the current
+/// page has been yielded, and the previous page is dropped before that happens. At most one page
+/// (up to [`ListParams::limit`] items) is held in memory at a time, regardless of how many items
+/// the endpoint returns in total.
+///
+/// The stream only moves forward from the `after` cursor, if any; params with a `before` cursor
+/// are rejected. Use a [`Paginator`] to page backward.
+pub struct ListStream<T, P = ListParams> {
+    http: Arc<HttpClient>,
+    path: String,
+    params: P,
+    page: std::vec::IntoIter<T>,
+    has_more: bool,
+}
+
+impl<T, P> ListStream<T, P>
+where
+    T: Resource + DeserializeOwned,
+    T::Id: AsRef<str>,
+    P: ListQuery,
+{
+    pub(crate) fn new(http: Arc<HttpClient>, path: impl Into<String>, params: P) -> Self {
+        Self {
+            http,
+            path: path.into(),
+            params,
+            page: Vec::new().into_iter(),
+            has_more: true,
+        }
+    }
+
+    /// Returns the next item, fetching the next page when the current one is exhausted.
+    ///
+    /// Returns `None` once the last page has been consumed. A failed page fetch, or params
+    /// that fail [`ListParams::validate`] or set `before`, is yielded as `Some(Err(_))` and
+    /// ends the stream.
+    pub async fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(item) = self.page.next() {
+                self.params.list_params_mut().after = Some(item.id().as_ref().to_string());
+                return Some(Ok(item));
+            }
+
+            if !self.has_more {
+                return None;
+            }
+
+            if let Err(e) = self.check_params() {
+                self.has_more = false;
+                return Some(Err(e));
+            }
+
+            match self
+                .http
+                .get_with_params::<_, List<T>>(&self.path, &self.params)
+                .await
+            {
+                Ok(list) => {
+                    self.has_more = list.has_more && !list.data.is_empty();
+                    self.page = list.data.into_iter();
+                }
+                Err(e) => {
+                    self.has_more = false;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Checks the params with [`ListParams::validate`] and rejects a `before` cursor, since the
+    /// stream only moves forward.
+    fn check_params(&self) -> Result<()> {
+        let list_params = self.params.list_params();
+        list_params.validate()?;
+        if list_params.before.is_some() {
+            return Err(Error::invalid_request(
+                "`before` is not supported by ListStream, use a Paginator to page backward",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the number of items from the current page that have not been yielded yet.
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.page.len()
+    }
+}
+
+/// Page-by-page navigation over a list endpoint, in both directions.
+///
+/// Suited to UIs with "next" and "previous" buttons: each call fetches one page, using the last
+/// item of the current page as the `after` cursor when moving forward and its first item as
+/// the `before` cursor when moving back. Only the current page is kept.
+pub struct Paginator<T> {
+    http: Arc<HttpClient>,
+    path: String,
+    params: ListParams,
+    current: Option<List<T>>,
+    has_next: bool,
+    has_prev: bool,
+}
+
+impl<T> Paginator<T>
+where
+    T: Resource + DeserializeOwned,
+    T::Id: AsRef<str>,
+{
+    pub(crate) fn new(http: Arc<HttpClient>, path: impl Into<String>, params: ListParams) -> Self {
+        Self {
+            http,
+            path: path.into(),
+            has_prev: params.after.is_some(),
+            params,
+            current: None,
+            has_next: true,
+        }
+    }
+
+    /// Fetches the page after the current one, or the first page on the first call.
+    ///
+    /// Returns `Ok(None)` without sending a request when there is no next page; the current
+    /// page is left unchanged.
+    pub async fn next_page(&mut self) -> Result<Option<&List<T>>> {
+        if !self.has_next {
+            return Ok(None);
+        }
+
+        if let Some(current) = &self.current {
+            let Some(last) = current.data.last() else {
+                return Ok(None);
+            };
+            self.params.after = Some(last.id().as_ref().to_string());
+            self.params.before = None;
+        }
+
+        let page = self.fetch().await?;
+        if self.current.is_some() {
+            self.has_prev = true;
+        }
+        self.has_next = page.has_more;
+        Ok(Some(self.current.insert(page)))
+    }
+
+    /// Fetches the page before the current one.
+    ///
+    /// Returns `Ok(None)` without sending a request when there is no previous page or no page
+    /// has been fetched yet; the current page is left unchanged.
+    pub async fn prev_page(&mut self) -> Result<Option<&List<T>>> {
+        if !self.has_prev {
+            return Ok(None);
+        }
+        let Some(first) = self
+            .current
+            .as_ref()
+            .and_then(|current| current.data.first())
+        else {
+            return Ok(None);
+        };
+        self.params.before = Some(first.id().as_ref().to_string());
+        self.params.after = None;
+
+        let page = self.fetch().await?;
+        self.has_next = true;
+        self.has_prev = page.has_more;
+        Ok(Some(self.current.insert(page)))
+    }
+
+    /// Returns the most recently fetched page.
+    #[must_use]
+    pub const fn current_page(&self) -> Option<&List<T>> {
+        self.current.as_ref()
+    }
+
+    async fn fetch(&self) -> Result<List<T>> {
+        self.params.validate()?;
+        self.http.get_with_params(&self.path, &self.params).await
+    }
+}
+
+impl<T> std::fmt::Debug for Paginator<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Paginator")
+            .field("path", &self.path)
+            .field("params", &self.params)
+            .field("has_next", &self.has_next)
+            .field("has_prev", &self.has_prev)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, P: std::fmt::Debug> std::fmt::Debug for ListStream<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ListStream")
+            .field("path", &self.path)
+            .field("params", &self.params)
+            .field("buffered", &self.page.len())
+            .field("has_more", &self.has_more)
+            .finish()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::assert_round_trip;
 
     #[test]
     fn test_list_empty() {
@@ -118,6 +401,58 @@ mod tests {
         assert!(!list.has_more);
     }
 
+    #[test]
+    fn test_list_slice_access() {
+        let list = List {
+            data: vec![3, 7, 5],
+            ..List::empty()
+        };
+
+        fn sum(values: &[i32]) -> i32 {
+            values.iter().sum()
+        }
+
+        assert_eq!(list.iter().max(), Some(&7));
+        assert_eq!(list.first(), Some(&3));
+        assert!(list.contains(&5));
+        assert_eq!(list.as_slice(), [3, 7, 5]);
+        assert_eq!(sum(list.as_ref()), 15);
+        assert_eq!(sum(&list), 15);
+    }
+
+    #[test]
+    fn test_list_to_ndjson() {
+        use crate::resources::payments::Payment;
+
+        let payment = |id: &str, amount: i64| -> Payment {
+            let mut json = crate::test_utils::payment_json();
+            json["id"] = id.into();
+            json["amount"] = amount.into();
+            json["net_amount"] = (amount - 350).into();
+            serde_json::from_value(json).unwrap()
+        };
+        let list = List {
+            data: vec![payment("pay_1", 10000), payment("pay_2", 20000)],
+            has_more: true,
+            ..List::empty()
+        };
+
+        let ndjson = list.to_ndjson().unwrap();
+        assert!(ndjson.ends_with('\n'));
+        let lines: Vec<_> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, expected) in lines.iter().zip(&list.data) {
+            let parsed: Payment = serde_json::from_str(line).unwrap();
+            assert_eq!(&parsed, expected);
+        }
+
+        let mut buffer = Vec::new();
+        list.write_ndjson(&mut buffer).unwrap();
+        assert_eq!(buffer, ndjson.as_bytes());
+
+        assert_eq!(List::<Payment>::empty().to_ndjson().unwrap(), "");
+    }
+
     #[test]
     fn test_list_with_data() {
         let list = List {
@@ -170,6 +505,16 @@ mod tests {
         assert_eq!(params.after, Some("obj_123".to_string()));
     }
 
+    #[test]
+    fn test_list_params_validate_cursors() {
+        assert!(ListParams::new().validate().is_ok());
+        assert!(ListParams::new().after("cus_1").validate().is_ok());
+        assert!(ListParams::new().before("cus_1").validate().is_ok());
+
+        let result = ListParams::new().after("cus_1").before("cus_2").validate();
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
     #[test]
     fn test_list_params_limit_clamping() {
         let params = ListParams::new().limit(200);
@@ -189,6 +534,8 @@ mod tests {
             total_count: Some(3),
         };
 
+        assert_round_trip(&list);
+
         let json = serde_json::to_string(&list).unwrap();
         assert!(json.contains("\"object\":\"list\""));
         assert!(json.contains("\"data\":[1,2,3]"));
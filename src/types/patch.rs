@@ -0,0 +1,112 @@
+//! Three-state fields for update requests.
+//!
+//! A plain `Option` cannot tell "leave this field alone" apart from "clear this field", so
+//! update parameters whose fields can be cleared use [`Patch`] instead.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A field of an update request that can be left unchanged, cleared, or set.
+///
+/// Fields of this type must be annotated with
+/// `#[serde(default, skip_serializing_if = "Patch::is_unchanged")]` so that
+/// [`Patch::Unchanged`] is omitted from the request. [`Patch::Clear`] is sent as `null` in JSON
+/// bodies and as an empty value (`field=`) in form bodies.
+///
+/// # Examples
+///
+/// ```
+/// use payrex::types::Patch;
+///
+/// let prefix: Patch<String> = Patch::from(Some("INV".to_string()));
+/// assert_eq!(prefix, Patch::Set("INV".to_string()));
+/// assert_eq!(Patch::<String>::from(None), Patch::Clear);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Patch<T> {
+    /// Leave the field as it is. The field is omitted from the request.
+    #[default]
+    Unchanged,
+
+    /// Remove the current value.
+    Clear,
+
+    /// Replace the current value.
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    /// Returns `true` for [`Patch::Unchanged`].
+    #[must_use]
+    pub const fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+
+    /// Returns the new value, if one is set.
+    #[must_use]
+    pub const fn as_set(&self) -> Option<&T> {
+        match self {
+            Self::Set(value) => Some(value),
+            Self::Unchanged | Self::Clear => None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    /// Maps `Some` to [`Patch::Set`] and `None` to [`Patch::Clear`].
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Clear, Self::Set)
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Set(value) => value.serialize(serializer),
+            Self::Unchanged | Self::Clear => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Params {
+        #[serde(default, skip_serializing_if = "Patch::is_unchanged")]
+        prefix: Patch<String>,
+    }
+
+    #[test]
+    fn test_patch_json() {
+        let cases = [
+            (Patch::Unchanged, serde_json::json!({})),
+            (Patch::Clear, serde_json::json!({ "prefix": null })),
+            (
+                Patch::Set("INV".to_string()),
+                serde_json::json!({ "prefix": "INV" }),
+            ),
+        ];
+
+        for (prefix, json) in cases {
+            let params = Params { prefix };
+            assert_eq!(serde_json::to_value(&params).unwrap(), json);
+            assert_eq!(serde_json::from_value::<Params>(json).unwrap(), params);
+        }
+    }
+
+    #[test]
+    fn test_patch_form() {
+        let form = |prefix| serde_qs::to_string(&Params { prefix }).unwrap();
+
+        assert_eq!(form(Patch::Unchanged), "");
+        assert_eq!(form(Patch::Clear), "prefix=");
+        assert_eq!(form(Patch::Set("INV".to_string())), "prefix=INV");
+    }
+}
@@ -1,40 +1,63 @@
 //! Types for payment methods, card options and capture methods.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Available payment methods for a [`PaymentIntent`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Methods this version of the SDK does not know about deserialize as
+/// [`PaymentMethod::Unknown`] with the raw value preserved, so new PayRex payment methods do not
+/// break deserialization of the resources that contain them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PaymentMethod {
     /// Card payments
-    #[serde(rename = "card")]
     Card,
 
     /// GCash payments
-    #[serde(rename = "gcash")]
     GCash,
 
     /// Maya payments
-    #[serde(rename = "maya")]
     Maya,
 
     /// QRPH payments
-    #[serde(rename = "qrph")]
     QRPh,
+
+    /// A payment method not yet supported by this SDK, holding the raw API value.
+    Unknown(String),
 }
 
 impl PaymentMethod {
     /// Returns the string representation of the payment method.
     #[must_use]
-    pub const fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Card => "card",
             Self::GCash => "gcash",
             Self::Maya => "maya",
             Self::QRPh => "qrph",
+            Self::Unknown(method) => method,
         }
     }
 }
 
+impl Serialize for PaymentMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PaymentMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let method = String::deserialize(deserializer)?;
+        Ok(match method.as_str() {
+            "card" => Self::Card,
+            "gcash" => Self::GCash,
+            "maya" => Self::Maya,
+            "qrph" => Self::QRPh,
+            _ => Self::Unknown(method),
+        })
+    }
+}
+
 /// A set of key-value pairs that can modify the behavior of the payment method attached to the
 /// payment intent.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,6 +104,28 @@ pub enum CaptureMethod {
     Manual,
 }
 
+/// Describes how a payment intent is confirmed once a payment method is attached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationMethod {
+    /// The payment intent is confirmed as soon as the customer submits a payment method.
+    Automatic,
+
+    /// The payment intent waits for your server to confirm it, e.g. after running its own checks.
+    Manual,
+}
+
+/// Indicates that the payment method should be saved for future payments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupFutureUsage {
+    /// The payment method will be reused while the customer is present in your checkout flow.
+    OnSession,
+
+    /// The payment method will be reused without the customer present, e.g. for subscriptions.
+    OffSession,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +143,52 @@ mod tests {
         assert_eq!(json, "\"manual\"");
     }
 
+    #[test]
+    fn test_confirmation_method_serialization() {
+        for (method, json) in [
+            (ConfirmationMethod::Automatic, "\"automatic\""),
+            (ConfirmationMethod::Manual, "\"manual\""),
+        ] {
+            assert_eq!(serde_json::to_string(&method).unwrap(), json);
+            assert_eq!(
+                serde_json::from_str::<ConfirmationMethod>(json).unwrap(),
+                method
+            );
+        }
+    }
+
+    #[test]
+    fn test_setup_future_usage_serialization() {
+        assert_eq!(
+            serde_json::to_string(&SetupFutureUsage::OnSession).unwrap(),
+            "\"on_session\""
+        );
+        assert_eq!(
+            serde_json::to_string(&SetupFutureUsage::OffSession).unwrap(),
+            "\"off_session\""
+        );
+    }
+
+    #[test]
+    fn test_payment_method_deserialization_keeps_unknown_methods() {
+        let method: PaymentMethod = serde_json::from_str("\"gcash\"").unwrap();
+        assert_eq!(method, PaymentMethod::GCash);
+
+        let method: PaymentMethod = serde_json::from_str("\"grabpay\"").unwrap();
+        assert_eq!(method, PaymentMethod::Unknown("grabpay".to_string()));
+        assert_eq!(method.as_str(), "grabpay");
+        assert_eq!(serde_json::to_string(&method).unwrap(), "\"grabpay\"");
+
+        let methods: Vec<PaymentMethod> = serde_json::from_str(r#"["card", "grabpay"]"#).unwrap();
+        assert_eq!(
+            methods,
+            [
+                PaymentMethod::Card,
+                PaymentMethod::Unknown("grabpay".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_payment_method_serialization() {
         use PaymentMethod::*;
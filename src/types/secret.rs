@@ -0,0 +1,93 @@
+//! Redacting wrapper for sensitive values.
+//!
+//! API keys and client secrets must never end up in logs, so they are wrapped in [`Secret`],
+//! which hides its contents from `Debug` and has no `Display` implementation.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A value that is redacted when formatted with `Debug`.
+///
+/// The inner value is serialized as-is so the wire format is unchanged. Read it explicitly with
+/// [`Secret::expose`].
+///
+/// # Examples
+///
+/// ```
+/// use payrex::types::Secret;
+///
+/// let secret = Secret::new("sk_test_123".to_string());
+/// assert_eq!(format!("{secret:?}"), "***");
+/// assert_eq!(secret.expose(), "sk_test_123");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wraps a sensitive value.
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns a reference to the wrapped value.
+    #[must_use]
+    pub const fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Consumes the wrapper and returns the wrapped value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl From<String> for Secret<String> {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_debug_is_redacted() {
+        let secret = Secret::new("sk_live_abc123".to_string());
+        let debug = format!("{secret:?}");
+        assert_eq!(debug, "***");
+        assert!(!debug.contains("sk_live_abc123"));
+    }
+
+    #[test]
+    fn test_secret_expose() {
+        let secret = Secret::from("value");
+        assert_eq!(secret.expose(), "value");
+        assert_eq!(secret.into_inner(), "value".to_string());
+    }
+
+    #[test]
+    fn test_secret_serialization() {
+        let secret = Secret::from("value");
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"value\"");
+
+        let secret: Secret<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(secret.expose(), "value");
+    }
+}
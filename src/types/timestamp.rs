@@ -2,7 +2,7 @@
 //!
 //! PayRex API uses Unix timestamps (seconds since epoch) for all date/time values.
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
@@ -14,9 +14,18 @@ use std::fmt;
 pub struct Timestamp(DateTime<Utc>);
 
 impl Timestamp {
+    /// Creates a timestamp from Unix seconds.
+    ///
+    /// Values outside the range `chrono` can represent saturate to [`DateTime::<Utc>::MIN_UTC`]
+    /// or [`DateTime::<Utc>::MAX_UTC`] instead of panicking, since timestamps may come from
+    /// untrusted payloads such as webhooks.
     #[must_use]
     pub fn from_unix(seconds: i64) -> Self {
-        Self(Utc.timestamp_opt(seconds, 0).unwrap())
+        let seconds = seconds.clamp(
+            DateTime::<Utc>::MIN_UTC.timestamp(),
+            DateTime::<Utc>::MAX_UTC.timestamp(),
+        );
+        Self(DateTime::from_timestamp(seconds, 0).unwrap_or(DateTime::<Utc>::MAX_UTC))
     }
 
     #[must_use]
@@ -39,9 +48,17 @@ impl Timestamp {
         self.0
     }
 
+    /// Formats the timestamp as RFC 3339.
+    ///
+    /// RFC 3339 only covers years 0 through 9999; timestamps outside that range are formatted
+    /// as `@<unix seconds>` instead.
     #[must_use]
     pub fn to_rfc3339(&self) -> String {
-        self.0.to_rfc3339()
+        if (0..=9999).contains(&self.0.year()) {
+            self.0.to_rfc3339()
+        } else {
+            format!("@{}", self.as_unix())
+        }
     }
 }
 
@@ -59,7 +76,7 @@ impl From<Timestamp> for DateTime<Utc> {
 
 impl fmt::Display for Timestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0.to_rfc3339())
+        write!(f, "{}", self.to_rfc3339())
     }
 }
 
@@ -82,6 +99,85 @@ impl<'de> Deserialize<'de> for Timestamp {
     }
 }
 
+/// Serde helpers that represent a [`Timestamp`] as an RFC 3339 string instead of Unix seconds.
+///
+/// Meant for storing resources in your own systems in a readable form; the API itself always
+/// uses Unix seconds, which is what the default `Serialize` implementation emits.
+///
+/// ```
+/// use payrex::types::Timestamp;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Record {
+///     #[serde(with = "payrex::types::timestamp::rfc3339")]
+///     paid_at: Timestamp,
+///     #[serde(default, with = "payrex::types::timestamp::rfc3339::option")]
+///     refunded_at: Option<Timestamp>,
+/// }
+///
+/// let record = Record { paid_at: Timestamp::from_unix(1_609_459_200), refunded_at: None };
+/// let json = serde_json::to_string(&record).unwrap();
+/// assert_eq!(json, r#"{"paid_at":"2021-01-01T00:00:00Z","refunded_at":null}"#);
+/// ```
+pub mod rfc3339 {
+    use super::Timestamp;
+    use chrono::{DateTime, SecondsFormat};
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    /// Serializes `timestamp` as an RFC 3339 string with second precision, e.g.
+    /// `2021-01-01T00:00:00Z`.
+    pub fn serialize<S>(timestamp: &Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(
+            &timestamp
+                .as_datetime()
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+    }
+
+    /// Deserializes a [`Timestamp`] from an RFC 3339 string in any offset.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        DateTime::parse_from_rfc3339(&value)
+            .map(|datetime| Timestamp::from(datetime.to_utc()))
+            .map_err(de::Error::custom)
+    }
+
+    /// The same representation for `Option<Timestamp>` fields, with `None` as `null`.
+    pub mod option {
+        use super::Timestamp;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        /// Serializes `Some` as an RFC 3339 string and `None` as `null`.
+        pub fn serialize<S>(timestamp: &Option<Timestamp>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match timestamp {
+                Some(timestamp) => super::serialize(timestamp, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserializes `null` as `None` and an RFC 3339 string as `Some`.
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Timestamp>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(Deserialize)]
+            struct Wrapper(#[serde(with = "super")] Timestamp);
+
+            Option::<Wrapper>::deserialize(deserializer).map(|value| value.map(|value| value.0))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,6 +204,61 @@ mod tests {
         assert_eq!(dt.timestamp(), 1609459200);
     }
 
+    #[test]
+    fn test_rfc3339_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Record {
+            #[serde(with = "rfc3339")]
+            at: Timestamp,
+            #[serde(default, with = "rfc3339::option")]
+            maybe: Option<Timestamp>,
+            plain: Timestamp,
+        }
+
+        let record = Record {
+            at: Timestamp::from_unix(1_609_459_200),
+            maybe: Some(Timestamp::from_unix(1_609_459_261)),
+            plain: Timestamp::from_unix(1_609_459_200),
+        };
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "at": "2021-01-01T00:00:00Z",
+                "maybe": "2021-01-01T00:01:01Z",
+                "plain": 1_609_459_200,
+            })
+        );
+        assert_eq!(serde_json::from_value::<Record>(json).unwrap(), record);
+
+        let record = Record {
+            maybe: None,
+            ..record
+        };
+        let json = serde_json::to_value(&record).unwrap();
+        assert!(json["maybe"].is_null());
+        assert_eq!(serde_json::from_value::<Record>(json).unwrap(), record);
+
+        let parsed: Record = serde_json::from_value(serde_json::json!({
+            "at": "2021-01-01T08:00:00+08:00",
+            "plain": 0,
+        }))
+        .unwrap();
+        assert_eq!(parsed.at, Timestamp::from_unix(1_609_459_200));
+        assert_eq!(parsed.maybe, None);
+
+        assert!(
+            serde_json::from_value::<Record>(
+                serde_json::json!({ "at": 1_609_459_200, "plain": 0 })
+            )
+            .is_err()
+        );
+        assert!(
+            serde_json::from_value::<Record>(serde_json::json!({ "at": "yesterday", "plain": 0 }))
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_timestamp_serialization() {
         let ts = Timestamp::from_unix(1609459200);
@@ -131,6 +282,41 @@ mod tests {
         assert!(ts2 > ts1);
     }
 
+    #[test]
+    fn test_timestamp_extreme_values_do_not_panic() {
+        let values = [
+            i64::MIN,
+            i64::MIN + 1,
+            -62_167_219_201, // one second before 0000-01-01T00:00:00
+            -1,
+            0,
+            253_402_300_800, // 10000-01-01T00:00:00
+            i64::MAX - 1,
+            i64::MAX,
+        ];
+
+        for value in values {
+            let ts: Timestamp = serde_json::from_str(&value.to_string()).unwrap();
+            let display = ts.to_string();
+            assert!(!display.is_empty());
+            assert_eq!(display, ts.to_rfc3339());
+            let _ = serde_json::to_string(&ts).unwrap();
+        }
+
+        assert_eq!(
+            Timestamp::from_unix(i64::MAX).to_string(),
+            format!("@{}", DateTime::<Utc>::MAX_UTC.timestamp())
+        );
+        assert_eq!(
+            Timestamp::from_unix(253_402_300_800).to_string(),
+            "@253402300800"
+        );
+        assert_eq!(
+            Timestamp::from_unix(0).to_string(),
+            "1970-01-01T00:00:00+00:00"
+        );
+    }
+
     #[test]
     fn test_timestamp_display() {
         let ts = Timestamp::from_unix(1609459200);
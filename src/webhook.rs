@@ -0,0 +1,285 @@
+//! Webhook signature verification.
+//!
+//! PayRex signs every webhook delivery with the secret key of the receiving webhook. The
+//! `Payrex-Signature` header has the form `t=<timestamp>,te=<test signature>,li=<live signature>`,
+//! where each signature is the hex-encoded HMAC-SHA256 of `<timestamp>.<raw body>`.
+//!
+//! The helpers here take the raw request body, never re-serialized JSON, so verification must
+//! happen before the body is parsed by a web framework.
+//!
+//! [Reference](https://docs.payrexhq.com/docs/guide/developer_handbook/webhooks)
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use http::HeaderMap;
+use sha2::Sha256;
+
+use crate::{
+    Error, Result,
+    types::{Secret, event::Event},
+};
+
+/// Name of the header carrying the webhook signature.
+pub const SIGNATURE_HEADER: &str = "payrex-signature";
+
+/// How far the signed timestamp may drift from the current time before a delivery is rejected.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// Checks the `Payrex-Signature` header value against the raw `payload`.
+///
+/// The delivery is accepted if either the test or live signature matches and the signed
+/// timestamp is within `tolerance` of the current time. Pass `None` to skip the timestamp
+/// check, e.g. when replaying stored deliveries.
+pub fn verify_signature(
+    payload: &[u8],
+    signature_header: &str,
+    secret: &str,
+    tolerance: Option<Duration>,
+) -> Result<()> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in signature_header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("te" | "li", value)) if !value.is_empty() => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| Error::signature_verification("missing timestamp"))?;
+    let signed_at: i64 = timestamp
+        .parse()
+        .map_err(|_| Error::signature_verification("invalid timestamp"))?;
+    if signatures.is_empty() {
+        return Err(Error::signature_verification("missing signature"));
+    }
+
+    if let Some(tolerance) = tolerance {
+        let age = chrono::Utc::now().timestamp().abs_diff(signed_at);
+        if age > tolerance.as_secs() {
+            return Err(Error::signature_verification(
+                "timestamp is outside the tolerance",
+            ));
+        }
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| Error::signature_verification("invalid secret"))?;
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+
+    let matches = signatures.iter().any(|signature| {
+        hex::decode(signature).is_ok_and(|expected| mac.clone().verify_slice(&expected).is_ok())
+    });
+    if matches {
+        Ok(())
+    } else {
+        Err(Error::signature_verification("signature does not match"))
+    }
+}
+
+/// Verifies `payload` with [`verify_signature`] and parses it into an [`Event`].
+///
+/// Use [`Event::check_api_version`] on the result to be warned about events rendered with an
+/// API version your integration does not expect.
+pub fn construct_event(payload: &[u8], signature_header: &str, secret: &str) -> Result<Event> {
+    verify_signature(payload, signature_header, secret, Some(DEFAULT_TOLERANCE))?;
+    Ok(serde_json::from_slice(payload)?)
+}
+
+/// Extracts the signature from `headers`, verifies `body`, and parses it into an [`Event`].
+///
+/// Works with any framework that exposes the request headers and raw body bytes.
+pub fn from_webhook_request(headers: &HeaderMap, body: &[u8], secret: &str) -> Result<Event> {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .ok_or_else(|| Error::signature_verification("missing Payrex-Signature header"))?
+        .to_str()
+        .map_err(|_| Error::signature_verification("invalid Payrex-Signature header"))?;
+    construct_event(body, signature, secret)
+}
+
+/// The secret key of a webhook, used by the [`VerifiedEvent`] extractor.
+///
+/// Provide it through the router state, directly or via `FromRef`.
+#[derive(Debug, Clone)]
+pub struct WebhookSecret(pub Secret<String>);
+
+/// An [`Event`] whose signature has been verified.
+///
+/// With the `axum` feature enabled this is an extractor, so a handler can be written as
+/// `async fn handler(VerifiedEvent(event): VerifiedEvent)`. Requests that fail verification
+/// are rejected with `400 Bad Request`. Bodies larger than the router's
+/// [`DefaultBodyLimit`](https://docs.rs/axum/latest/axum/extract/struct.DefaultBodyLimit.html)
+/// (2 MB unless configured) are rejected with `413 Payload Too Large` before being verified.
+#[derive(Debug, Clone)]
+pub struct VerifiedEvent(pub Event);
+
+#[cfg(feature = "axum")]
+impl<S> axum::extract::FromRequest<S> for VerifiedEvent
+where
+    S: Send + Sync,
+    WebhookSecret: axum::extract::FromRef<S>,
+{
+    type Rejection = (http::StatusCode, String);
+
+    async fn from_request(
+        req: axum::extract::Request,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let secret = <WebhookSecret as axum::extract::FromRef<S>>::from_ref(state);
+        let headers = req.headers().clone();
+        let body = axum::body::Bytes::from_request(req, state)
+            .await
+            .map_err(|rejection| (rejection.status(), rejection.body_text()))?;
+        from_webhook_request(&headers, &body, secret.0.expose())
+            .map(Self)
+            .map_err(|e| (http::StatusCode::BAD_REQUEST, e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    const SECRET: &str = "whsk_test_secret";
+
+    fn payload() -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "id": "evt_123",
+            "type": "payment_intent.succeeded",
+            "data": { "resource": { "id": "pi_123" } },
+            "livemode": false,
+            "created_at": 1_620_000_000,
+            "updated_at": 1_620_000_000,
+        }))
+        .unwrap()
+    }
+
+    fn sign(payload: &[u8], timestamp: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(format!("{timestamp}.").as_bytes());
+        mac.update(payload);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn now() -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = payload();
+        let t = now();
+        let signature = sign(&body, t);
+
+        let header = format!("t={t},te={signature},li=");
+        assert!(verify_signature(&body, &header, SECRET, Some(DEFAULT_TOLERANCE)).is_ok());
+
+        let header = format!("t={t},te=,li={signature}");
+        assert!(verify_signature(&body, &header, SECRET, Some(DEFAULT_TOLERANCE)).is_ok());
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_bad_input() {
+        let body = payload();
+        let t = now();
+        let signature = sign(&body, t);
+        let header = format!("t={t},te={signature}");
+
+        let mut tampered = body.clone();
+        tampered.push(b' ');
+        assert!(matches!(
+            verify_signature(&tampered, &header, SECRET, None),
+            Err(Error::SignatureVerification(_))
+        ));
+        assert!(verify_signature(&body, &header, "whsk_other", None).is_err());
+        assert!(verify_signature(&body, &format!("te={signature}"), SECRET, None).is_err());
+        assert!(verify_signature(&body, &format!("t={t},te=zz"), SECRET, None).is_err());
+        assert!(verify_signature(&body, &format!("t={t}"), SECRET, None).is_err());
+    }
+
+    #[test]
+    fn test_verify_signature_tolerance() {
+        let body = payload();
+        let t = now() - 600;
+        let header = format!("t={t},te={}", sign(&body, t));
+
+        assert!(verify_signature(&body, &header, SECRET, Some(DEFAULT_TOLERANCE)).is_err());
+        assert!(verify_signature(&body, &header, SECRET, None).is_ok());
+    }
+
+    #[test]
+    fn test_from_webhook_request() {
+        let body = payload();
+        let t = now();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Payrex-Signature",
+            HeaderValue::from_str(&format!("t={t},te={},li=", sign(&body, t))).unwrap(),
+        );
+
+        let event = from_webhook_request(&headers, &body, SECRET).unwrap();
+        assert_eq!(event.id.as_str(), "evt_123");
+        assert_eq!(event.resource()["id"], "pi_123");
+
+        assert!(matches!(
+            from_webhook_request(&HeaderMap::new(), &body, SECRET),
+            Err(Error::SignatureVerification(m)) if m.contains("header")
+        ));
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_verified_event_extractor() {
+        use axum::extract::FromRequest;
+
+        let state = WebhookSecret(Secret::from(SECRET));
+        let body = payload();
+        let t = now();
+        let request = |signature: String| {
+            http::Request::builder()
+                .method("POST")
+                .header(SIGNATURE_HEADER, format!("t={t},te={signature}"))
+                .body(axum::body::Body::from(body.clone()))
+                .unwrap()
+        };
+
+        let VerifiedEvent(event) = VerifiedEvent::from_request(request(sign(&body, t)), &state)
+            .await
+            .unwrap();
+        assert_eq!(event.id.as_str(), "evt_123");
+
+        let (status, _) = VerifiedEvent::from_request(request("00".to_string()), &state)
+            .await
+            .unwrap_err();
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_verified_event_extractor_limits_body_size() {
+        use axum::extract::FromRequest;
+
+        let state = WebhookSecret(Secret::from(SECRET));
+        let request = http::Request::builder()
+            .method("POST")
+            .header(SIGNATURE_HEADER, "t=0,te=00")
+            .body(axum::body::Body::from(vec![b' '; 3 * 1024 * 1024]))
+            .unwrap();
+
+        let (status, _) = VerifiedEvent::from_request(request, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(status, http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn test_webhook_secret_debug_is_redacted() {
+        let secret = WebhookSecret(Secret::from(SECRET));
+        assert!(!format!("{secret:?}").contains(SECRET));
+    }
+}